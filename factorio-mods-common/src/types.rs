@@ -3,7 +3,7 @@
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct Url(pub String);
 
@@ -27,7 +27,7 @@ impl serde::Serialize for ModName {
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct AuthorName(pub String);
 
@@ -36,7 +36,7 @@ pub struct AuthorName(pub String);
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct ModTitle(pub String);
 
@@ -45,7 +45,7 @@ pub struct ModTitle(pub String);
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct ModDescription(pub String);
 
@@ -57,6 +57,21 @@ pub struct ModDescription(pub String);
 )]
 pub struct ReleaseVersion(#[serde(deserialize_with = "deserialize_version")] pub semver::Version);
 
+impl serde::Serialize for ReleaseVersion {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		serializer.serialize_str(&self.0.to_string())
+	}
+}
+
+impl std::str::FromStr for ReleaseVersion {
+	type Err = semver::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let version = s.parse().or_else(|_| fixup_version(s).parse())?;
+		Ok(ReleaseVersion(version))
+	}
+}
+
 /// A username and token used with the parts of the web API that require authentication.
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct UserCredentials {
@@ -118,6 +133,46 @@ impl<'de> serde::Deserialize<'de> for Dependency {
 	}
 }
 
+impl std::fmt::Display for Dependency {
+	/// Formats the dependency the way it's written in a mod's info.json, like `? name >= 1.2.3`
+	///
+	/// Factorio only supports a single comparison with a full version. Other requirements are written in semver syntax,
+	/// which the game can't parse.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.kind {
+			package::DependencyKind::Conflicts => f.write_str("! ")?,
+			package::DependencyKind::Optional => f.write_str("? ")?,
+			package::DependencyKind::Required => (),
+		}
+
+		write!(f, "{}", self.name)?;
+
+		match &*self.version.0.comparators {
+			[] => Ok(()),
+
+			[semver::Comparator { op, major, minor: Some(minor), patch: Some(patch), pre }] if pre.is_empty() => {
+				let op = match op {
+					semver::Op::Exact => "=",
+					semver::Op::Greater => ">",
+					semver::Op::GreaterEq => ">=",
+					semver::Op::Less => "<",
+					semver::Op::LessEq => "<=",
+					_ => return write!(f, " {}", self.version),
+				};
+				write!(f, " {op} {major}.{minor}.{patch}")
+			},
+
+			_ => write!(f, " {}", self.version),
+		}
+	}
+}
+
+impl serde::Serialize for Dependency {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		serializer.collect_str(self)
+	}
+}
+
 impl<'a> package::Dependency<'a, ReleaseVersion> for Dependency {
 	type Name = ModName;
 	type VersionReq = VersionReqMatcher<'a>;
@@ -302,4 +357,29 @@ mod tests {
 		test_deserialize_dependency_inner(r#""? some name with spaces >= 1.2.3""#, "some name with spaces", ">=1.2.3", package::DependencyKind::Optional);
 		test_deserialize_dependency_inner(r#""!foo""#, "foo", "*", package::DependencyKind::Conflicts);
 	}
+
+	fn test_display_dependency_inner(name: &str, version: &str, kind: package::DependencyKind, expected: &str) {
+		let dependency = super::Dependency { name: super::ModName(name.to_owned()), version: super::ModVersionReq(version.parse().unwrap()), kind };
+		let actual = dependency.to_string();
+		assert_eq!(actual, expected);
+
+		let roundtripped: super::Dependency = serde_json::from_value(serde_json::to_value(&dependency).unwrap()).unwrap();
+		assert_eq!(roundtripped, dependency);
+	}
+
+	#[test]
+	fn test_display_dependency() {
+		test_display_dependency_inner("base", "*", package::DependencyKind::Required, "base");
+		test_display_dependency_inner("base", ">=0.14.0", package::DependencyKind::Required, "base >= 0.14.0");
+		test_display_dependency_inner("foo", "=1.2.3", package::DependencyKind::Optional, "? foo = 1.2.3");
+		test_display_dependency_inner("some name with spaces", "<2.0.0", package::DependencyKind::Conflicts, "! some name with spaces < 2.0.0");
+		assert_eq!(
+			super::Dependency {
+				name: super::ModName("foo".to_owned()),
+				version: super::ModVersionReq("^1.2".parse().unwrap()),
+				kind: package::DependencyKind::Required,
+			}.to_string(),
+			"foo ^1.2",
+		);
+	}
 }
@@ -40,7 +40,7 @@ pub fn deserialize_string_or_seq_string<'de, T, D>(deserializer: D) -> Result<Ve
 // Tries to deserialize the given string as a newtype
 struct StringNewTypeStructDeserializer<'a, E>(&'a str, std::marker::PhantomData<E>);
 
-impl<'de, 'a, E> serde::Deserializer<'de> for StringNewTypeStructDeserializer<'a, E> where E: serde::de::Error {
+impl<'de, E> serde::Deserializer<'de> for StringNewTypeStructDeserializer<'_, E> where E: serde::de::Error {
 	type Error = E;
 
 	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
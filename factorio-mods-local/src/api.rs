@@ -4,12 +4,20 @@ pub struct Api {
 	game_version: factorio_mods_common::ReleaseVersion,
 	mods_directory: std::path::PathBuf,
 	mod_list_file_path: std::path::PathBuf,
+	mod_settings_file_path: std::path::PathBuf,
 	player_data_json_file_path: std::path::PathBuf,
+	saves_directory: std::path::PathBuf,
 }
 
 impl Api {
 	/// Constructs an API client.
-	pub fn new(install_directory: &std::path::Path, user_directory: &std::path::Path) -> Result<Self, crate::Error> {
+	///
+	/// The mods directory defaults to the `mods` directory inside the user directory.
+	pub fn new(
+		install_directory: &std::path::Path,
+		user_directory: &std::path::Path,
+		mods_directory: Option<&std::path::Path>,
+	) -> Result<Self, crate::Error> {
 		let game_version = {
 			let mut base_info_file_path = install_directory.join("data");
 			base_info_file_path.push("base");
@@ -29,22 +37,40 @@ impl Api {
 		};
 
 		let (mods_directory, mod_list_file_path, player_data_json_file_path) = {
-			let mods_directory = user_directory.join("mods");
-			let mod_list_file_path = mods_directory.join("mod-list.json");
 			let player_data_json_file_path = user_directory.join("player-data.json");
-			if mod_list_file_path.is_file() && player_data_json_file_path.is_file() {
-				(mods_directory, mod_list_file_path, player_data_json_file_path)
+			if !player_data_json_file_path.is_file() {
+				return Err(crate::Error::UserDirectoryNotFound);
+			}
+
+			if let Some(mods_directory) = mods_directory {
+				let mod_list_file_path = mods_directory.join("mod-list.json");
+				if !mod_list_file_path.is_file() {
+					return Err(crate::Error::ModsDirectoryNotFound(mods_directory.to_owned()));
+				}
+
+				(mods_directory.to_owned(), mod_list_file_path, player_data_json_file_path)
 			}
 			else {
-				return Err(crate::Error::UserDirectoryNotFound);
+				let mods_directory = user_directory.join("mods");
+				let mod_list_file_path = mods_directory.join("mod-list.json");
+				if !mod_list_file_path.is_file() {
+					return Err(crate::Error::UserDirectoryNotFound);
+				}
+
+				(mods_directory, mod_list_file_path, player_data_json_file_path)
 			}
 		};
 
+		let mod_settings_file_path = mods_directory.join("mod-settings.dat");
+		let saves_directory = user_directory.join("saves");
+
 		Ok(Api {
 			game_version,
 			mods_directory,
 			mod_list_file_path,
+			mod_settings_file_path,
 			player_data_json_file_path,
+			saves_directory,
 		})
 	}
 
@@ -58,11 +84,53 @@ impl Api {
 		&self.mods_directory
 	}
 
-	/// Returns an iterator over all the locally installed mods, matching the given name pattern if any.
+	/// Returns the directory where the game stores saves.
+	pub fn saves_directory(&self) -> &std::path::Path {
+		&self.saves_directory
+	}
+
+	/// Returns an iterator over all the locally installed mods.
 	pub fn installed_mods(&self) -> Result<impl Iterator<Item = Result<crate::InstalledMod, crate::Error>> + 'static, crate::Error> {
 		crate::installed_mod::find(&self.mods_directory, None, None)
 	}
 
+	/// Returns an iterator over the locally installed mods whose names match the given glob pattern if any,
+	/// and whose version is the given version if any.
+	pub fn find_installed_mods(
+		&self,
+		name_pattern: Option<&str>,
+		version: Option<&factorio_mods_common::ReleaseVersion>,
+	) -> Result<impl Iterator<Item = Result<crate::InstalledMod, crate::Error>> + 'static, crate::Error> {
+		crate::installed_mod::find(&self.mods_directory, name_pattern.map(ToOwned::to_owned), version.cloned())
+	}
+
+	/// Returns an iterator over the mod lists of all the saves in the saves directory.
+	///
+	/// Returns an empty iterator if the saves directory doesn't exist.
+	pub fn saves(&self) -> Result<impl Iterator<Item = Result<crate::Save, crate::Error>> + 'static, crate::Error> {
+		let directory_entries = match std::fs::read_dir(&self.saves_directory) {
+			Ok(directory_entries) => Some(directory_entries),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+			Err(err) => return Err(crate::Error::Io(self.saves_directory.clone(), err)),
+		};
+
+		let saves_directory = self.saves_directory.clone();
+
+		Ok(directory_entries.into_iter().flatten().filter_map(move |directory_entry| {
+			let directory_entry = match directory_entry {
+				Ok(directory_entry) => directory_entry,
+				Err(err) => return Some(Err(crate::Error::Io(saves_directory.clone(), err))),
+			};
+
+			let path = directory_entry.path();
+			if !path.is_file() || path.extension() != Some("zip".as_ref()) {
+				return None;
+			}
+
+			Some(crate::Save::parse(path))
+		}))
+	}
+
 	/// Fetches the locally saved user credentials, if any.
 	pub fn user_credentials(&self) -> Result<factorio_mods_common::UserCredentials, crate::Error> {
 		let player_data_json_file_path = &self.player_data_json_file_path;
@@ -121,13 +189,35 @@ impl Api {
 		Ok(mod_list.mods.into_iter().map(|m| (m.name.into_owned(), m.enabled)).collect())
 	}
 
+	/// Returns a map of installed mod name to the version of it that is chosen in `mod-list.json`, for the mods that have a chosen version.
+	///
+	/// The game loads the newest installed version of mods that don't have a chosen version.
+	pub fn active_versions(&self) -> Result<std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>, crate::Error> {
+		let mod_list = self.load_mod_list()?;
+		Ok(mod_list.mods.into_iter().filter_map(|m| Some((m.name.into_owned(), m.version?.into_owned()))).collect())
+	}
+
 	/// Marks the given locally installed mods as enabled or disabled in `mod-list.json`
+	///
+	/// If more than one version of a mod is installed, the version of the given installed mod is also recorded as the chosen version.
 	pub fn set_enabled<'a, I>(&self, installed_mods: I, enabled: bool) -> Result<(), crate::Error> where I: IntoIterator<Item = &'a crate::InstalledMod> {
+		let mut num_installed_versions: std::collections::BTreeMap<_, usize> = Default::default();
+		for installed_mod in self.installed_mods()? {
+			*num_installed_versions.entry(installed_mod?.info.name).or_default() += 1;
+		}
+
 		let mod_list = self.load_mod_list()?;
-		let mut mods_status: std::collections::BTreeMap<_, _> = mod_list.mods.into_iter().map(|m| (m.name, m.enabled)).collect();
+		let mut mods_status: std::collections::BTreeMap<_, _> = mod_list.mods.into_iter().map(|m| (m.name, (m.enabled, m.version))).collect();
 
 		for installed_mod in installed_mods {
-			mods_status.insert(std::borrow::Cow::Borrowed(&installed_mod.info.name), enabled);
+			let version =
+				if num_installed_versions.get(&installed_mod.info.name).copied().unwrap_or_default() > 1 {
+					Some(std::borrow::Cow::Borrowed(&installed_mod.info.version))
+				}
+				else {
+					None
+				};
+			mods_status.insert(std::borrow::Cow::Borrowed(&installed_mod.info.name), (enabled, version));
 		}
 
 		let mod_list_file_path = &self.mod_list_file_path;
@@ -137,7 +227,7 @@ impl Api {
 
 		let mut mods: Vec<_> =
 			mods_status.into_iter()
-			.map(|(name, enabled)| ModListMod { name, enabled })
+			.map(|(name, (enabled, version))| ModListMod { name, enabled, version })
 			.collect();
 		mods.sort_by(|mod1, mod2| mod1.name.cmp(&mod2.name));
 
@@ -149,6 +239,76 @@ impl Api {
 		Ok(())
 	}
 
+	/// Reads the mod settings from `mod-settings.dat`
+	///
+	/// If the file doesn't exist yet, as is the case before the game has been started with any mods, empty settings are returned.
+	pub fn mod_settings(&self) -> Result<crate::ModSettings, crate::Error> {
+		let mod_settings_file_path = &self.mod_settings_file_path;
+
+		let mod_settings_file = match std::fs::File::open(mod_settings_file_path) {
+			Ok(mod_settings_file) => mod_settings_file,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(crate::ModSettings::new(&self.game_version)),
+			Err(err) => return Err(crate::Error::Io(mod_settings_file_path.clone(), err)),
+		};
+
+		let mut mod_settings_file = std::io::BufReader::new(mod_settings_file);
+		crate::ModSettings::read(&mut mod_settings_file)
+			.map_err(|err| crate::Error::ReadModSettings(mod_settings_file_path.clone(), err))
+	}
+
+	/// Saves the given mod settings to `mod-settings.dat`
+	pub fn save_mod_settings(&self, mod_settings: &crate::ModSettings) -> Result<(), crate::Error> {
+		let mod_settings_file_path = &self.mod_settings_file_path;
+
+		let mut buf = vec![];
+		mod_settings.write(&mut buf).map_err(|err| crate::Error::Io(mod_settings_file_path.clone(), err))?;
+
+		std::fs::write(mod_settings_file_path, buf).map_err(|err| crate::Error::Io(mod_settings_file_path.clone(), err))?;
+
+		Ok(())
+	}
+
+	/// Returns a map of setting name to the name of the installed mod that defines it, for the given settings.
+	///
+	/// Settings that don't belong to any installed mod, such as those of mods that have been uninstalled, are not in the map.
+	///
+	/// Mods define their settings in Lua, so this can only be a best guess. A setting belongs to a mod if the mod's settings files
+	/// mention the setting's name as a string literal, else to the mod with the longest name that the setting name is prefixed with,
+	/// since mods conventionally prefix their settings with their own name.
+	pub fn setting_owners<'a>(
+		&self,
+		setting_names: impl IntoIterator<Item = &'a str>,
+	) -> Result<std::collections::BTreeMap<String, factorio_mods_common::ModName>, crate::Error> {
+		let mut defined_settings = std::collections::BTreeMap::new();
+		let mut installed_mod_names = std::collections::BTreeSet::new();
+		for installed_mod in self.installed_mods()? {
+			let installed_mod = installed_mod?;
+			for setting_name in installed_mod.setting_names()? {
+				defined_settings.insert(setting_name, installed_mod.info.name.clone());
+			}
+			installed_mod_names.insert(installed_mod.info.name);
+		}
+
+		let mut result = std::collections::BTreeMap::new();
+
+		for setting_name in setting_names {
+			let owner =
+				defined_settings.get(setting_name).cloned()
+				.or_else(||
+					installed_mod_names.iter()
+					.filter(|mod_name|
+						setting_name.strip_prefix(&*mod_name.0)
+						.is_some_and(|rest| rest.starts_with(['-', '_', ':'])))
+					.max_by_key(|mod_name| mod_name.0.len())
+					.cloned());
+			if let Some(owner) = owner {
+				result.insert(setting_name.to_owned(), owner);
+			}
+		}
+
+		Ok(result)
+	}
+
 	fn load_mod_list(&self) -> Result<ModList<'static>, crate::Error> {
 		let mod_list_file_path = &self.mod_list_file_path;
 		let mod_list_file =
@@ -175,6 +335,10 @@ struct ModListMod<'a> {
 
 	#[serde(deserialize_with = "deserialize_mod_list_mod_enabled")]
 	enabled: bool,
+
+	/// The version of the mod to load, if more than one version is installed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	version: Option<std::borrow::Cow<'a, factorio_mods_common::ReleaseVersion>>,
 }
 
 /// Represents the contents of `base/info.json`
@@ -13,15 +13,24 @@ pub enum Error {
 	/// An I/O error.
 	Io(std::path::PathBuf, std::io::Error),
 
+	/// The given mods directory does not contain a `mod-list.json`
+	ModsDirectoryNotFound(std::path::PathBuf),
+
 	/// Generating a glob from a pattern failed.
 	Pattern(String, globset::Error),
 
 	/// Reading a JSON file failed.
 	ReadJsonFile(std::path::PathBuf, serde_json::Error),
 
+	/// Reading `mod-settings.dat` failed.
+	ReadModSettings(std::path::PathBuf, std::io::Error),
+
 	/// The file or directory is not recognized as a valid mod format.
 	UnknownModFormat(std::path::PathBuf),
 
+	/// The mod list could not be found in a save file.
+	UnknownSaveFormat(std::path::PathBuf),
+
 	/// The Factorio user directory could not be found.
 	UserDirectoryNotFound,
 
@@ -39,9 +48,12 @@ impl std::fmt::Display for Error {
 			Error::IncompleteUserCredentials(_) => f.write_str("valid API credentials were not found in player-data.json"),
 			Error::InstallDirectoryNotFound => f.write_str("the local Factorio installation could not be found"),
 			Error::Io(path, _) => write!(f, "I/O error on {}", path.display()),
+			Error::ModsDirectoryNotFound(path) => write!(f, "the mods directory {} does not contain a mod-list.json", path.display()),
 			Error::Pattern(pattern, _) => write!(f, "the pattern {pattern} is invalid"),
 			Error::ReadJsonFile(path, _) => write!(f, "could not parse the JSON file {}", path.display()),
+			Error::ReadModSettings(path, _) => write!(f, "could not parse the mod settings file {}", path.display()),
 			Error::UnknownModFormat(path) => write!(f, "the mod at {} could not be recognized as a valid mod", path.display()),
+			Error::UnknownSaveFormat(path) => write!(f, "could not find the list of mods in the save file {}", path.display()),
 			Error::UserDirectoryNotFound => f.write_str("the Factorio user directory could not be found"),
 			Error::WriteJsonFile(path, _) => write!(f, "could not save {}", path.display()),
 			Error::Zip(path, _) => write!(f, "could not parse the ZIP file {}", path.display()),
@@ -57,9 +69,12 @@ impl std::error::Error for Error {
 			Error::IncompleteUserCredentials(_) => None,
 			Error::InstallDirectoryNotFound => None,
 			Error::Io(_, err) => Some(err),
+			Error::ModsDirectoryNotFound(_) => None,
 			Error::Pattern(_, err) => Some(err),
 			Error::ReadJsonFile(_, err) => Some(err),
+			Error::ReadModSettings(_, err) => Some(err),
 			Error::UnknownModFormat(_) => None,
+			Error::UnknownSaveFormat(_) => None,
 			Error::UserDirectoryNotFound => None,
 			Error::WriteJsonFile(_, err) => Some(err),
 			Error::Zip(_, err) => Some(err),
@@ -71,20 +71,7 @@ impl InstalledMod {
 				Err(err) => return Err(crate::Error::Zip(path, err)),
 			};
 
-			if zip_file.is_empty() {
-				return Err(crate::Error::EmptyZippedMod(path));
-			}
-
-			let toplevel = {
-				let first_file = match zip_file.by_index(0) {
-					Ok(first_file) => first_file,
-					Err(err) => return Err(crate::Error::Zip(path, err)),
-				};
-
-				let first_file_name = first_file.name();
-				let (toplevel, _) = first_file_name.split_once('/').unwrap_or((first_file_name, ""));
-				toplevel.to_owned()
-			};
+			let toplevel = zip_toplevel(&mut zip_file, &path)?;
 
 			let info_json_file_path = format!("{toplevel}/info.json");
 
@@ -117,9 +104,72 @@ impl InstalledMod {
 
 		Ok(InstalledMod { path, info, mod_type })
 	}
+
+	/// Returns the names of the settings that this mod appears to define.
+	///
+	/// Settings are defined by Lua code in the mod's settings stage files, so this only finds the names that appear as
+	/// `name = "..."` string literals in those files. Names that the code computes at runtime are not found.
+	pub fn setting_names(&self) -> Result<std::collections::BTreeSet<String>, crate::Error> {
+		static SETTING_NAME_REGEX: std::sync::LazyLock<regex::Regex> =
+			std::sync::LazyLock::new(|| regex::Regex::new(r#"\bname\s*=\s*(?:"([^"]+)"|'([^']+)')"#).unwrap());
+
+		const SETTINGS_FILE_NAMES: [&str; 3] = ["settings.lua", "settings-updates.lua", "settings-final-fixes.lua"];
+
+		let mut contents = vec![];
+
+		match self.mod_type {
+			InstalledModType::Zipped => {
+				let zip_file = std::fs::File::open(&self.path).map_err(|err| crate::Error::Io(self.path.clone(), err))?;
+				let mut zip_file = zip::ZipArchive::new(zip_file).map_err(|err| crate::Error::Zip(self.path.clone(), err))?;
+				let toplevel = zip_toplevel(&mut zip_file, &self.path)?;
+
+				for settings_file_name in SETTINGS_FILE_NAMES {
+					let mut settings_file = match zip_file.by_name(&format!("{toplevel}/{settings_file_name}")) {
+						Ok(settings_file) => settings_file,
+						Err(zip::result::ZipError::FileNotFound) => continue,
+						Err(err) => return Err(crate::Error::Zip(self.path.clone(), err)),
+					};
+
+					let mut settings_file_contents = String::new();
+					std::io::Read::read_to_string(&mut settings_file, &mut settings_file_contents)
+						.map_err(|err| crate::Error::Io(self.path.clone(), err))?;
+					contents.push(settings_file_contents);
+				}
+			},
+
+			InstalledModType::Unpacked => for settings_file_name in SETTINGS_FILE_NAMES {
+				let settings_file_path = self.path.join(settings_file_name);
+				match std::fs::read_to_string(&settings_file_path) {
+					Ok(settings_file_contents) => contents.push(settings_file_contents),
+					Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+					Err(err) => return Err(crate::Error::Io(settings_file_path, err)),
+				}
+			},
+		}
+
+		Ok(
+			contents.iter()
+			.flat_map(|contents| SETTING_NAME_REGEX.captures_iter(contents))
+			.filter_map(|captures| captures.get(1).or_else(|| captures.get(2)))
+			.map(|name| name.as_str().to_owned())
+			.collect())
+	}
+}
+
+/// Returns the name of the top-level directory of a zipped mod or save, which contains all its files.
+pub(crate) fn zip_toplevel(zip_file: &mut zip::ZipArchive<std::fs::File>, path: &std::path::Path) -> Result<String, crate::Error> {
+	if zip_file.is_empty() {
+		return Err(crate::Error::EmptyZippedMod(path.to_owned()));
+	}
+
+	let first_file = zip_file.by_index(0).map_err(|err| crate::Error::Zip(path.to_owned(), err))?;
+
+	let first_file_name = first_file.name();
+	let (toplevel, _) = first_file_name.split_once('/').unwrap_or((first_file_name, ""));
+	Ok(toplevel.to_owned())
 }
 
-/// Constructs an iterator over all the locally installed mods.
+/// Constructs an iterator over the locally installed mods whose names match the given glob pattern and version, if any.
 pub fn find(
 	mods_directory: &std::path::Path,
 	name_pattern: Option<String>,
@@ -128,7 +178,16 @@ pub fn find(
 	let directory_entries = std::fs::read_dir(mods_directory).map_err(|err| crate::Error::Io(mods_directory.to_owned(), err))?;
 
 	let name_pattern = name_pattern.map_or(std::borrow::Cow::Borrowed("*"), std::borrow::Cow::Owned);
-	let matcher = globset::Glob::new(&name_pattern).map_err(|err| crate::Error::Pattern(name_pattern.into_owned(), err))?.compile_matcher();
+	let matcher = globset::Glob::new(&name_pattern).map_err(|err| crate::Error::Pattern(name_pattern.to_string(), err))?.compile_matcher();
+
+	// Mods are named `{name}` or `{name}_{version}`, with a `.zip` extension if they're zipped,
+	// so the filenames of mods that can't match the pattern are skipped without opening them.
+	let filename_matcher =
+		globset::GlobSetBuilder::new()
+		.add(globset::Glob::new(&name_pattern).map_err(|err| crate::Error::Pattern(name_pattern.to_string(), err))?)
+		.add(globset::Glob::new(&format!("{name_pattern}_*")).map_err(|err| crate::Error::Pattern(name_pattern.to_string(), err))?)
+		.build()
+		.map_err(|err| crate::Error::Pattern(name_pattern.into_owned(), err))?;
 
 	Ok(directory_entries
 		.filter_map({
@@ -142,8 +201,8 @@ pub fn find(
 
 				let path = directory_entry.path();
 
-				let matches = path.file_name().map_or(false, |filename| matcher.is_match(filename));
-				if !matches {
+				let stem = if path.extension() == Some("zip".as_ref()) { path.file_stem() } else { path.file_name() };
+				if !stem.is_some_and(|stem| filename_matcher.is_match(stem)) {
 					return None;
 				}
 
@@ -153,6 +212,11 @@ pub fn find(
 					Err(err) => return Some(Err(err)),
 				};
 
+				// Match the pattern against the mod's name rather than its filename, since the filename also contains the version.
+				if !matcher.is_match(&installed_mod.info.name.0) {
+					return None;
+				}
+
 				if let Some(version) = &version {
 					if version != &installed_mod.info.version {
 						return None;
@@ -187,3 +251,38 @@ fn default_dependencies() -> Vec<factorio_mods_common::Dependency> {
 
 	DEFAULT_DEPENDENCIES.clone()
 }
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_find() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let mods_directory = temp_dir.path();
+
+		for (directory_name, name) in [("foo", "foo"), ("foo_bar_1.0.0", "foo_bar"), ("qux_2.0.0", "qux")] {
+			std::fs::create_dir_all(mods_directory.join(directory_name)).unwrap();
+			std::fs::write(
+				mods_directory.join(directory_name).join("info.json"),
+				format!(r#"{{ "name": "{name}", "version": "1.0.0", "title": "", "author": "" }}"#),
+			).unwrap();
+		}
+
+		// A corrupt zip only breaks the patterns that match its filename.
+		std::fs::write(mods_directory.join("baz_1.0.0.zip"), b"not a zip file").unwrap();
+
+		let find = |name_pattern: Option<&str>| -> Result<Vec<String>, crate::Error> {
+			let mut names: Vec<_> =
+				super::find(mods_directory, name_pattern.map(ToOwned::to_owned), None).unwrap()
+				.map(|installed_mod| installed_mod.map(|installed_mod| installed_mod.info.name.0))
+				.collect::<Result<_, _>>()?;
+			names.sort();
+			Ok(names)
+		};
+
+		assert_eq!(find(Some("foo")).unwrap(), ["foo"]);
+		assert_eq!(find(Some("foo*")).unwrap(), ["foo", "foo_bar"]);
+		assert_eq!(find(Some("q?x")).unwrap(), ["qux"]);
+		assert!(find(Some("ba*")).is_err());
+		assert!(find(None).is_err());
+	}
+}
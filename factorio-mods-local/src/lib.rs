@@ -10,3 +10,12 @@ pub use self::error::Error;
 
 mod installed_mod;
 pub use self::installed_mod::{ InstalledMod, InstalledModType, ModInfo };
+
+mod mod_settings;
+pub use self::mod_settings::{ ModSettings, SettingType };
+
+mod property_tree;
+pub use self::property_tree::PropertyTree;
+
+mod save;
+pub use self::save::{ Save, SaveMod };
@@ -0,0 +1,180 @@
+/// The contents of `mod-settings.dat`
+///
+/// The file is a property tree with one dictionary per setting type, each of which maps setting names to
+/// a dictionary with the setting's value under the `value` key.
+///
+/// Ref: <https://wiki.factorio.com/Mod_settings_file_format>
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModSettings {
+	/// The version of the game that wrote the file, as major, minor, patch and build numbers.
+	version: [u16; 4],
+
+	tree: crate::PropertyTree,
+}
+
+/// The type of a mod setting, which determines when the game reads it.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SettingType {
+	/// Read once when the game starts. Changing it requires restarting the game.
+	Startup,
+
+	/// Shared by all players of a map.
+	RuntimeGlobal,
+
+	/// Specific to each player.
+	RuntimePerUser,
+}
+
+impl SettingType {
+	/// All setting types, in the order the game lists them.
+	pub const ALL: [Self; 3] = [SettingType::Startup, SettingType::RuntimeGlobal, SettingType::RuntimePerUser];
+
+	/// The name of this setting type in `mod-settings.dat`
+	pub fn name(self) -> &'static str {
+		match self {
+			SettingType::Startup => "startup",
+			SettingType::RuntimeGlobal => "runtime-global",
+			SettingType::RuntimePerUser => "runtime-per-user",
+		}
+	}
+}
+
+impl std::fmt::Display for SettingType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl std::str::FromStr for SettingType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		SettingType::ALL.into_iter()
+			.find(|setting_type| setting_type.name() == s)
+			.ok_or_else(|| format!(r#"expected one of "startup", "runtime-global" or "runtime-per-user", got {s:?}"#))
+	}
+}
+
+impl ModSettings {
+	/// Constructs an empty set of mod settings for the given game version.
+	pub fn new(game_version: &factorio_mods_common::ReleaseVersion) -> Self {
+		let version_part = |part: u64| part.try_into().unwrap_or(u16::MAX);
+
+		ModSettings {
+			version: [version_part(game_version.0.major), version_part(game_version.0.minor), version_part(game_version.0.patch), 0],
+			tree: crate::PropertyTree::Dictionary(
+				SettingType::ALL.into_iter()
+				.map(|setting_type| (setting_type.name().to_owned(), crate::PropertyTree::Dictionary(vec![])))
+				.collect()),
+		}
+	}
+
+	/// Reads mod settings from the given reader.
+	pub fn read(reader: &mut impl std::io::Read) -> std::io::Result<Self> {
+		let mut version = [0_u16; 4];
+		for part in &mut version {
+			*part = crate::property_tree::read_u16(reader)?;
+		}
+
+		// Files written by 0.17 and later have an extra unused byte after the version.
+		if (version[0], version[1]) >= (0, 17) {
+			let mut buf = [0_u8; 1];
+			reader.read_exact(&mut buf)?;
+		}
+
+		let tree = crate::PropertyTree::read(reader)?;
+		if !matches!(tree, crate::PropertyTree::Dictionary(_)) {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "mod settings are not a dictionary"));
+		}
+
+		Ok(ModSettings { version, tree })
+	}
+
+	/// Writes these mod settings to the given writer.
+	pub fn write(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+		for part in self.version {
+			crate::property_tree::write_u16(writer, part)?;
+		}
+
+		if (self.version[0], self.version[1]) >= (0, 17) {
+			writer.write_all(&[0])?;
+		}
+
+		self.tree.write(writer)
+	}
+
+	/// Returns an iterator over all the settings, as their type, name and value.
+	pub fn settings(&self) -> impl Iterator<Item = (SettingType, &str, &crate::PropertyTree)> {
+		SettingType::ALL.into_iter().flat_map(move |setting_type| {
+			let settings = match self.tree.get(setting_type.name()) {
+				Some(crate::PropertyTree::Dictionary(settings)) => &settings[..],
+				_ => &[],
+			};
+
+			settings.iter().filter_map(move |(name, setting)| Some((setting_type, &**name, setting.get("value")?)))
+		})
+	}
+
+	/// Returns the type and value of the setting with the given name, if it exists.
+	pub fn get(&self, name: &str) -> Option<(SettingType, &crate::PropertyTree)> {
+		self.settings().find_map(|(setting_type, setting_name, value)| (setting_name == name).then_some((setting_type, value)))
+	}
+
+	/// Sets the value of the setting with the given name and type.
+	///
+	/// If a setting with the same name exists with a different type, it is removed.
+	pub fn set(&mut self, setting_type: SettingType, name: &str, value: crate::PropertyTree) {
+		for other_setting_type in SettingType::ALL {
+			if other_setting_type != setting_type {
+				self.remove_of_type(other_setting_type, name);
+			}
+		}
+
+		let settings = self.settings_of_type_mut(setting_type);
+
+		let setting = crate::PropertyTree::Dictionary(vec![("value".to_owned(), value)]);
+		if let Some((_, existing)) = settings.iter_mut().find(|(setting_name, _)| setting_name == name) {
+			*existing = setting;
+		}
+		else {
+			settings.push((name.to_owned(), setting));
+		}
+	}
+
+	/// Removes the setting with the given name, so that the game resets it to its default value.
+	///
+	/// Returns `true` if the setting existed.
+	pub fn remove(&mut self, name: &str) -> bool {
+		let mut removed = false;
+		for setting_type in SettingType::ALL {
+			removed |= self.remove_of_type(setting_type, name);
+		}
+		removed
+	}
+
+	fn remove_of_type(&mut self, setting_type: SettingType, name: &str) -> bool {
+		let settings = self.settings_of_type_mut(setting_type);
+		let original_len = settings.len();
+		settings.retain(|(setting_name, _)| setting_name != name);
+		settings.len() != original_len
+	}
+
+	fn settings_of_type_mut(&mut self, setting_type: SettingType) -> &mut Vec<(String, crate::PropertyTree)> {
+		let crate::PropertyTree::Dictionary(root) = &mut self.tree else {
+			unreachable!("the root of the mod settings is always a dictionary");
+		};
+
+		let index = root.iter().position(|(key, _)| key == setting_type.name()).unwrap_or_else(|| {
+			root.push((setting_type.name().to_owned(), crate::PropertyTree::Dictionary(vec![])));
+			root.len() - 1
+		});
+
+		let settings = &mut root[index].1;
+		if !matches!(settings, crate::PropertyTree::Dictionary(_)) {
+			*settings = crate::PropertyTree::Dictionary(vec![]);
+		}
+
+		let crate::PropertyTree::Dictionary(settings) = settings else { unreachable!() };
+		settings
+	}
+}
@@ -0,0 +1,286 @@
+#![allow(
+	clippy::cast_possible_truncation,
+)]
+
+/// A node of a property tree, the binary format that the game uses for `mod-settings.dat` among other files.
+///
+/// Ref: <https://wiki.factorio.com/Property_tree>
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyTree {
+	/// No value.
+	None,
+
+	/// A boolean.
+	Bool(bool),
+
+	/// A double-precision floating point number.
+	Number(f64),
+
+	/// A string.
+	String(String),
+
+	/// A list of nodes.
+	List(Vec<PropertyTree>),
+
+	/// A dictionary of nodes, in the order they were read.
+	Dictionary(Vec<(String, PropertyTree)>),
+
+	/// A signed integer.
+	SignedInteger(i64),
+
+	/// An unsigned integer.
+	UnsignedInteger(u64),
+}
+
+impl PropertyTree {
+	/// Reads a property tree from the given reader.
+	pub fn read(reader: &mut impl std::io::Read) -> std::io::Result<Self> {
+		let r#type = read_u8(reader)?;
+
+		// "Any-type" flag. It is only used by the game internally.
+		let _ = read_u8(reader)?;
+
+		Ok(match r#type {
+			0 => PropertyTree::None,
+
+			1 => PropertyTree::Bool(read_u8(reader)? != 0),
+
+			2 => {
+				let mut buf = [0_u8; 8];
+				reader.read_exact(&mut buf)?;
+				PropertyTree::Number(f64::from_le_bytes(buf))
+			},
+
+			3 => PropertyTree::String(read_string(reader)?),
+
+			4 => {
+				let len = read_u32(reader)?;
+				let mut list = vec![];
+				for _ in 0..len {
+					// List elements have (empty) keys too.
+					let _ = read_string(reader)?;
+					list.push(PropertyTree::read(reader)?);
+				}
+				PropertyTree::List(list)
+			},
+
+			5 => {
+				let len = read_u32(reader)?;
+				let mut dictionary = vec![];
+				for _ in 0..len {
+					let key = read_string(reader)?;
+					let value = PropertyTree::read(reader)?;
+					dictionary.push((key, value));
+				}
+				PropertyTree::Dictionary(dictionary)
+			},
+
+			6 => {
+				let mut buf = [0_u8; 8];
+				reader.read_exact(&mut buf)?;
+				PropertyTree::SignedInteger(i64::from_le_bytes(buf))
+			},
+
+			7 => {
+				let mut buf = [0_u8; 8];
+				reader.read_exact(&mut buf)?;
+				PropertyTree::UnsignedInteger(u64::from_le_bytes(buf))
+			},
+
+			r#type => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("unknown property tree type {type}"))),
+		})
+	}
+
+	/// Writes this property tree to the given writer.
+	pub fn write(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+		let r#type: u8 = match self {
+			PropertyTree::None => 0,
+			PropertyTree::Bool(_) => 1,
+			PropertyTree::Number(_) => 2,
+			PropertyTree::String(_) => 3,
+			PropertyTree::List(_) => 4,
+			PropertyTree::Dictionary(_) => 5,
+			PropertyTree::SignedInteger(_) => 6,
+			PropertyTree::UnsignedInteger(_) => 7,
+		};
+		writer.write_all(&[r#type, 0])?;
+
+		match self {
+			PropertyTree::None => (),
+
+			PropertyTree::Bool(value) => writer.write_all(&[u8::from(*value)])?,
+
+			PropertyTree::Number(value) => writer.write_all(&value.to_le_bytes())?,
+
+			PropertyTree::String(value) => write_string(writer, value)?,
+
+			PropertyTree::List(list) => {
+				write_u32(writer, list.len())?;
+				for value in list {
+					write_string(writer, "")?;
+					value.write(writer)?;
+				}
+			},
+
+			PropertyTree::Dictionary(dictionary) => {
+				write_u32(writer, dictionary.len())?;
+				for (key, value) in dictionary {
+					write_string(writer, key)?;
+					value.write(writer)?;
+				}
+			},
+
+			PropertyTree::SignedInteger(value) => writer.write_all(&value.to_le_bytes())?,
+
+			PropertyTree::UnsignedInteger(value) => writer.write_all(&value.to_le_bytes())?,
+		}
+
+		Ok(())
+	}
+
+	/// Returns the value of the given key if this is a dictionary that contains the key.
+	pub fn get(&self, key: &str) -> Option<&PropertyTree> {
+		match self {
+			PropertyTree::Dictionary(dictionary) => dictionary.iter().find_map(|(k, v)| (k == key).then_some(v)),
+			_ => None,
+		}
+	}
+
+	/// Returns a mutable reference to the value of the given key if this is a dictionary that contains the key.
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut PropertyTree> {
+		match self {
+			PropertyTree::Dictionary(dictionary) => dictionary.iter_mut().find_map(|(k, v)| (k == key).then_some(v)),
+			_ => None,
+		}
+	}
+}
+
+fn read_u8(reader: &mut impl std::io::Read) -> std::io::Result<u8> {
+	let mut buf = [0_u8; 1];
+	reader.read_exact(&mut buf)?;
+	Ok(buf[0])
+}
+
+pub(crate) fn read_u16(reader: &mut impl std::io::Read) -> std::io::Result<u16> {
+	let mut buf = [0_u8; 2];
+	reader.read_exact(&mut buf)?;
+	Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32(reader: &mut impl std::io::Read) -> std::io::Result<u32> {
+	let mut buf = [0_u8; 4];
+	reader.read_exact(&mut buf)?;
+	Ok(u32::from_le_bytes(buf))
+}
+
+/// Reads a space-optimized u32, which is a single byte if it's less than 255, else 255 followed by the full u32.
+pub(crate) fn read_optimized_u32(reader: &mut impl std::io::Read) -> std::io::Result<u32> {
+	match read_u8(reader)? {
+		255 => read_u32(reader),
+		value => Ok(value.into()),
+	}
+}
+
+/// Reads a string, which is an "is empty" flag followed by a space-optimized length and the UTF-8 contents.
+fn read_string(reader: &mut impl std::io::Read) -> std::io::Result<String> {
+	if read_u8(reader)? != 0 {
+		return Ok(String::new());
+	}
+
+	// The length comes from the file, so read through `take` instead of allocating all of it upfront.
+	let len = read_optimized_u32(reader)?;
+	let mut buf = vec![];
+	std::io::Read::read_to_end(&mut std::io::Read::take(reader, len.into()), &mut buf)?;
+	if buf.len() != len as usize {
+		return Err(std::io::ErrorKind::UnexpectedEof.into());
+	}
+
+	String::from_utf8(buf).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+pub(crate) fn write_u16(writer: &mut impl std::io::Write, value: u16) -> std::io::Result<()> {
+	writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u32(writer: &mut impl std::io::Write, value: usize) -> std::io::Result<()> {
+	let value: u32 = value.try_into().map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+	writer.write_all(&value.to_le_bytes())
+}
+
+fn write_string(writer: &mut impl std::io::Write, value: &str) -> std::io::Result<()> {
+	if value.is_empty() {
+		return writer.write_all(&[1]);
+	}
+
+	writer.write_all(&[0])?;
+
+	if value.len() < 255 {
+		writer.write_all(&[value.len() as u8])?;
+	}
+	else {
+		writer.write_all(&[255])?;
+		write_u32(writer, value.len())?;
+	}
+
+	writer.write_all(value.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_roundtrip() {
+		let tree = super::PropertyTree::Dictionary(vec![
+			("startup".to_owned(), super::PropertyTree::Dictionary(vec![
+				("some-bool-setting".to_owned(), super::PropertyTree::Dictionary(vec![
+					("value".to_owned(), super::PropertyTree::Bool(true)),
+				])),
+				("some-string-setting".to_owned(), super::PropertyTree::Dictionary(vec![
+					("value".to_owned(), super::PropertyTree::String("a".repeat(300))),
+				])),
+			])),
+			("runtime-global".to_owned(), super::PropertyTree::Dictionary(vec![
+				("some-number-setting".to_owned(), super::PropertyTree::Dictionary(vec![
+					("value".to_owned(), super::PropertyTree::Number(1.5)),
+				])),
+				("some-list".to_owned(), super::PropertyTree::List(vec![
+					super::PropertyTree::SignedInteger(-1),
+					super::PropertyTree::UnsignedInteger(1),
+					super::PropertyTree::String(String::new()),
+					super::PropertyTree::None,
+				])),
+			])),
+		]);
+
+		let mut buf = vec![];
+		tree.write(&mut buf).unwrap();
+
+		let actual = super::PropertyTree::read(&mut &buf[..]).unwrap();
+		assert_eq!(actual, tree);
+	}
+
+	#[test]
+	fn test_read() {
+		let buf = [
+			5, 0, // Dictionary
+			1, 0, 0, 0, // One entry
+			0, 3, b'f', b'o', b'o', // Key "foo"
+			3, 0, // String
+			0, 3, b'b', b'a', b'r', // Value "bar"
+		];
+
+		let actual = super::PropertyTree::read(&mut &buf[..]).unwrap();
+		assert_eq!(actual, super::PropertyTree::Dictionary(vec![("foo".to_owned(), super::PropertyTree::String("bar".to_owned()))]));
+	}
+
+	#[test]
+	fn test_read_truncated_string() {
+		let buf = [
+			3, 0, // String
+			0, 255, 255, 255, 255, 255, // Length u32::MAX
+			b'f', b'o', b'o',
+		];
+
+		let err = super::PropertyTree::read(&mut &buf[..]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+	}
+}
@@ -0,0 +1,213 @@
+/// The mods that a saved game was made with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Save {
+	/// The path of the save file.
+	pub path: std::path::PathBuf,
+
+	/// The version of the game that wrote the save.
+	pub game_version: factorio_mods_common::ReleaseVersion,
+
+	/// The mods that were enabled when the game was saved, including `base`
+	pub mods: Vec<SaveMod>,
+}
+
+/// A mod that a saved game was made with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SaveMod {
+	/// The name of the mod.
+	pub name: factorio_mods_common::ModName,
+
+	/// The version of the mod.
+	pub version: factorio_mods_common::ReleaseVersion,
+
+	/// The checksum that the game computed over the mod's files.
+	pub crc: u32,
+}
+
+/// The files inside a save that start with the map header, in order of preference.
+///
+/// Newer saves have a small `level-init.dat` and split the full map over `level.dat0`, `level.dat1`, ...; older saves have a single `level.dat`
+const LEVEL_FILE_NAMES: [&str; 3] = ["level-init.dat", "level.dat0", "level.dat"];
+
+/// Only this much of the map header is read, since the mod list is near its start and the rest of the map can be very large.
+const MAX_HEADER_LEN: u64 = 1024 * 1024;
+
+impl Save {
+	/// Parses the save at the given location.
+	pub fn parse(path: std::path::PathBuf) -> Result<Self, crate::Error> {
+		let zip_file = match std::fs::File::open(&path) {
+			Ok(zip_file) => zip_file,
+			Err(err) => return Err(crate::Error::Io(path, err)),
+		};
+
+		let mut zip_file = match zip::ZipArchive::new(zip_file) {
+			Ok(zip_file) => zip_file,
+			Err(err) => return Err(crate::Error::Zip(path, err)),
+		};
+
+		let toplevel = crate::installed_mod::zip_toplevel(&mut zip_file, &path)?;
+
+		let mut header = None;
+
+		for level_file_name in LEVEL_FILE_NAMES {
+			let level_file = match zip_file.by_name(&format!("{toplevel}/{level_file_name}")) {
+				Ok(level_file) => level_file,
+				Err(zip::result::ZipError::FileNotFound) => continue,
+				Err(err) => return Err(crate::Error::Zip(path, err)),
+			};
+
+			match read_header(level_file) {
+				Ok(level_file_header) => {
+					header = Some(level_file_header);
+					break;
+				},
+
+				Err(err) => return Err(crate::Error::Io(path, err)),
+			}
+		}
+
+		let Some((game_version, mods)) = header.as_deref().and_then(parse_header) else {
+			return Err(crate::Error::UnknownSaveFormat(path));
+		};
+
+		Ok(Save { path, game_version, mods })
+	}
+}
+
+/// Reads the start of the given level file, decompressing it if necessary.
+fn read_header(mut level_file: impl std::io::Read) -> std::io::Result<Vec<u8>> {
+	let mut magic = [0_u8; 2];
+	level_file.read_exact(&mut magic)?;
+	let level_file = std::io::Read::chain(&magic[..], level_file);
+
+	let mut header = vec![];
+
+	// Newer saves compress the level files with zlib.
+	if magic[0] == 0x78 && u16::from_be_bytes(magic) % 31 == 0 {
+		let level_file = libflate::zlib::Decoder::new(level_file)?;
+		std::io::Read::read_to_end(&mut std::io::Read::take(level_file, MAX_HEADER_LEN), &mut header)?;
+	}
+	else {
+		std::io::Read::read_to_end(&mut std::io::Read::take(level_file, MAX_HEADER_LEN), &mut header)?;
+	}
+
+	Ok(header)
+}
+
+/// Parses the game version and mod list from the given map header.
+///
+/// The fields between the version and the mod list have changed between game versions, so rather than parse them,
+/// this looks for the mod list by its first entry, which is always `base` with the same version as the save itself.
+fn parse_header(header: &[u8]) -> Option<(factorio_mods_common::ReleaseVersion, Vec<SaveMod>)> {
+	let mut reader = header;
+	let major = crate::property_tree::read_u16(&mut reader).ok()?;
+	let minor = crate::property_tree::read_u16(&mut reader).ok()?;
+	let patch = crate::property_tree::read_u16(&mut reader).ok()?;
+
+	let game_version = factorio_mods_common::ReleaseVersion(semver::Version::new(major.into(), minor.into(), patch.into()));
+
+	let mut base_entry = vec![4];
+	base_entry.extend_from_slice(b"base");
+	for part in [major, minor, patch] {
+		write_optimized_u16(&mut base_entry, part);
+	}
+
+	let mods =
+		(8..header.len())
+		.filter(|&i| header[i..].starts_with(&base_entry))
+		.find_map(|i| {
+			// The mod list is preceded by the number of mods as a space-optimized u32.
+			let num_mods = match header[..i] {
+				[.., 255, a, b, c, d] => u32::from_le_bytes([a, b, c, d]),
+				[.., num_mods] if num_mods != 255 => num_mods.into(),
+				_ => return None,
+			};
+
+			parse_mods(&header[i..], num_mods)
+		})?;
+
+	Some((game_version, mods))
+}
+
+fn parse_mods(mut reader: &[u8], num_mods: u32) -> Option<Vec<SaveMod>> {
+	let mut mods = vec![];
+
+	for _ in 0..num_mods {
+		let name_len = crate::property_tree::read_optimized_u32(&mut reader).ok()?;
+		let name_len = usize::try_from(name_len).ok()?;
+		if name_len == 0 || name_len > reader.len() {
+			return None;
+		}
+		let (name, rest) = reader.split_at(name_len);
+		let name = std::str::from_utf8(name).ok()?.to_owned();
+		reader = rest;
+
+		let major = read_optimized_u16(&mut reader)?;
+		let minor = read_optimized_u16(&mut reader)?;
+		let patch = read_optimized_u16(&mut reader)?;
+		let version = factorio_mods_common::ReleaseVersion(semver::Version::new(major.into(), minor.into(), patch.into()));
+
+		let mut crc = [0_u8; 4];
+		std::io::Read::read_exact(&mut reader, &mut crc).ok()?;
+		let crc = u32::from_le_bytes(crc);
+
+		mods.push(SaveMod { name: factorio_mods_common::ModName(name), version, crc });
+	}
+
+	Some(mods)
+}
+
+/// Reads a space-optimized u16, which is a single byte if it's less than 255, else 255 followed by the full u16.
+fn read_optimized_u16(reader: &mut &[u8]) -> Option<u16> {
+	let (&first, rest) = reader.split_first()?;
+	*reader = rest;
+	match first {
+		255 => crate::property_tree::read_u16(reader).ok(),
+		value => Some(value.into()),
+	}
+}
+
+fn write_optimized_u16(buf: &mut Vec<u8>, value: u16) {
+	match u8::try_from(value) {
+		Ok(value) if value != 255 => buf.push(value),
+		_ => {
+			buf.push(255);
+			buf.extend_from_slice(&value.to_le_bytes());
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse_header() {
+		let mut header = vec![
+			1, 0, 1, 0, 110, 0, 0, 0, // Version 1.1.110.0
+			0, // Unused
+			4, b'b', b'a', b's', b'e', // Base mod name, which must not be mistaken for the mod list
+			0, 0, 0,
+		];
+
+		header.push(2); // Two mods
+		header.extend_from_slice(&[4, b'b', b'a', b's', b'e', 1, 1, 110]);
+		header.extend_from_slice(&0x1234_5678_u32.to_le_bytes());
+		header.extend_from_slice(&[3, b'f', b'o', b'o', 0, 255, 0x2c, 0x01, 3]);
+		header.extend_from_slice(&0x9abc_def0_u32.to_le_bytes());
+		header.extend_from_slice(&[0, 0, 0]); // Rest of the header
+
+		let (game_version, mods) = super::parse_header(&header).unwrap();
+		assert_eq!(game_version.0, semver::Version::new(1, 1, 110));
+		assert_eq!(mods, [
+			super::SaveMod {
+				name: factorio_mods_common::ModName("base".to_owned()),
+				version: factorio_mods_common::ReleaseVersion(semver::Version::new(1, 1, 110)),
+				crc: 0x1234_5678,
+			},
+			super::SaveMod {
+				name: factorio_mods_common::ModName("foo".to_owned()),
+				version: factorio_mods_common::ReleaseVersion(semver::Version::new(0, 300, 3)),
+				crc: 0x9abc_def0,
+			},
+		]);
+	}
+}
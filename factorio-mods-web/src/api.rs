@@ -5,40 +5,101 @@ pub struct Api {
 	mods_url: url::Url,
 	login_url: url::Url,
 	client: crate::client::Client,
+
+	/// Whether `base_url` is the official mods portal. User credentials are only sent to the official mods portal.
+	is_official: bool,
 }
 
 impl Api {
 	/// Constructs an API client with the given parameters.
 	pub fn new() -> Result<Self, crate::Error> {
+		Self::with_base_url("https://mods.factorio.com/".parse().expect("hard-coded URL must parse successfully"))
+	}
+
+	/// Constructs an API client for the server at the given base URL, which must serve the same API as the mods portal.
+	///
+	/// Requests to the server's host are allowed even though it isn't whitelisted, but they never contain user credentials
+	/// unless the server is the official mods portal.
+	pub fn with_base_url(mut base_url: url::Url) -> Result<Self, crate::Error> {
+		// Relative URLs are joined to the base URL, which would otherwise replace its last path segment.
+		if !base_url.path().ends_with('/') {
+			let path = format!("{}/", base_url.path());
+			base_url.set_path(&path);
+		}
+
+		let mods_url = match base_url.join("api/mods?page_size=10000") {
+			Ok(mods_url) => mods_url,
+			Err(err) => return Err(crate::Error::Parse(format!("{base_url}api/mods"), err)),
+		};
+
+		let is_official = base_url.scheme() == "https" && base_url.host_str() == Some("mods.factorio.com");
+
+		let extra_host = if is_official { None } else { base_url.host_str().map(ToOwned::to_owned) };
+
 		Ok(Api {
-			base_url: "https://mods.factorio.com/".parse().expect("hard-coded URL must parse successfully"),
-			mods_url: "https://mods.factorio.com/api/mods?page_size=10000".parse().expect("hard-coded URL must parse successfully"),
+			base_url,
+			mods_url,
 			login_url: "https://auth.factorio.com/api-login".parse().expect("hard-coded URL must parse successfully"),
-			client: crate::client::Client::new(),
+			client: crate::client::Client::new(extra_host),
+			is_official,
 		})
 	}
 
+	/// The base URL of the server.
+	pub fn base_url(&self) -> &url::Url {
+		&self.base_url
+	}
+
+	/// Whether the server is the official mods portal. User credentials are only sent to the official mods portal.
+	pub fn is_official(&self) -> bool {
+		self.is_official
+	}
+
 	/// Searches for mods matching the given criteria.
 	pub fn search(&self, query: &str) -> impl futures_core::Stream<Item = Result<crate::SearchResponseMod, crate::Error>> + '_ {
 		let query = query.to_lowercase();
 
-		let mut next_page_url = self.mods_url.clone();
+		let pages = self.pages(self.mods_url.clone());
 
+		Box::pin(async_stream::try_stream! {
+			futures_util::pin_mut!(pages);
+
+			while let Some(page) = futures_util::TryStreamExt::try_next(&mut pages).await? {
+				for mod_ in page {
+					if
+						mod_.name.0.to_lowercase().contains(&query) ||
+						mod_.title.0.to_lowercase().contains(&query) ||
+						mod_.owner.iter().any(|owner| owner.0.to_lowercase().contains(&query)) ||
+						mod_.summary.0.to_lowercase().contains(&query)
+					{
+						yield mod_;
+					}
+				}
+			}
+		})
+	}
+
+	/// Lists all mods a page at a time, most recently updated first.
+	///
+	/// Callers that keep a copy of the mods list can stop reading pages once they reach mods that haven't changed since their copy was made.
+	pub fn recently_updated(&self) -> impl futures_core::Stream<Item = Result<Vec<crate::SearchResponseMod>, crate::Error>> + '_ {
+		let mut url = self.mods_url.clone();
+		url.query_pairs_mut()
+			.clear()
+			.append_pair("page_size", "100")
+			.append_pair("sort", "updated_at")
+			.append_pair("sort_order", "desc");
+		self.pages(url)
+	}
+
+	/// Gets the pages of mods starting at the given URL, following the links to the next pages.
+	fn pages(&self, mut next_page_url: url::Url) -> impl futures_core::Stream<Item = Result<Vec<crate::SearchResponseMod>, crate::Error>> + '_ {
 		Box::pin(async_stream::try_stream! {
 			loop {
 				let next_page: Result<(PagedResponse<crate::SearchResponseMod>, _), _> = self.client.get_object(next_page_url).await;
 				match next_page {
 					Ok((page, _)) => {
-						for mod_ in page.results {
-							if
-								mod_.name.0.to_lowercase().contains(&query) ||
-								mod_.title.0.to_lowercase().contains(&query) ||
-								mod_.owner.iter().any(|owner| owner.0.to_lowercase().contains(&query)) ||
-								mod_.summary.0.to_lowercase().contains(&query)
-							{
-								yield mod_;
-							}
-						}
+						yield page.results;
 
 						let Some(next_page_url_) = page.pagination.and_then(|pagination| pagination.links.next) else { return; };
 						next_page_url = next_page_url_;
@@ -55,9 +116,12 @@ impl Api {
 		})
 	}
 
-	/// Gets information about the specified mod.
+	/// Gets information about the specified mod, including its releases.
+	///
+	/// The details that only [`Api::get_full`] gets, like the mod's description, are not set.
 	pub fn get(&self, mod_name: &factorio_mods_common::ModName) -> impl std::future::Future<Output = Result<crate::Mod, crate::Error>> {
 		let mut mod_url = self.mods_url.clone();
+		mod_url.set_query(None);
 		mod_url.path_segments_mut().unwrap().push(&mod_name.0);
 		let future = self.client.get_object(mod_url);
 
@@ -67,6 +131,21 @@ impl Api {
 		}
 	}
 
+	/// Gets full information about the specified mod, including details like its description, tags and license.
+	///
+	/// The response is much larger than that of [`Api::get`], so this should only be used to show the details.
+	pub fn get_full(&self, mod_name: &factorio_mods_common::ModName) -> impl std::future::Future<Output = Result<crate::Mod, crate::Error>> {
+		let mut mod_url = self.mods_url.clone();
+		mod_url.set_query(None);
+		mod_url.path_segments_mut().unwrap().push(&mod_name.0).push("full");
+		let future = self.client.get_object(mod_url);
+
+		async move {
+			let (mod_, _) = future.await?;
+			Ok(mod_)
+		}
+	}
+
 	/// Logs in to the web API using the given username and password and returns a credentials object.
 	pub fn login(
 		&self,
@@ -89,9 +168,11 @@ impl Api {
 	) -> impl std::future::Future<Output = Result<u64, crate::Error>> {
 		let future = match self.base_url.join(&release.download_url.0) {
 			Ok(mut download_url) => {
-				download_url.query_pairs_mut()
-					.append_pair("username", &user_credentials.username.0)
-					.append_pair("token", &user_credentials.token.0);
+				if self.is_official {
+					download_url.query_pairs_mut()
+						.append_pair("username", &user_credentials.username.0)
+						.append_pair("token", &user_credentials.token.0);
+				}
 				Ok(self.client.head_zip(download_url))
 			},
 
@@ -124,9 +205,11 @@ impl Api {
 	) -> impl futures_core::Stream<Item = Result<bytes::Bytes, crate::Error>> {
 		let future = match self.base_url.join(&release.download_url.0) {
 			Ok(mut download_url) => {
-				download_url.query_pairs_mut()
-					.append_pair("username", &user_credentials.username.0)
-					.append_pair("token", &user_credentials.token.0);
+				if self.is_official {
+					download_url.query_pairs_mut()
+						.append_pair("username", &user_credentials.username.0)
+						.append_pair("token", &user_credentials.token.0);
+				}
 
 				let range = match range {
 					Some(range) => match range.parse() {
@@ -169,6 +252,25 @@ impl Api {
 			}
 		}
 	}
+
+	/// Downloads the file at the given URL, which can be on any host, and returns a reader to the file contents.
+	///
+	/// This is for files that aren't part of the mods portal, like the files of a mod repository. No user credentials are sent.
+	pub fn get_file(&self, url: &str) -> impl futures_core::Stream<Item = Result<bytes::Bytes, crate::Error>> {
+		let future = match url.parse() {
+			Ok(url) => Ok(self.client.get_any(url)),
+			Err(err) => Err(crate::Error::Parse(url.to_owned(), err)),
+		};
+
+		async_stream::try_stream! {
+			let (response, url) = future?.await?;
+			let mut response = response.into_body();
+
+			while let Some(chunk) = futures_util::TryStreamExt::try_next(&mut response).await.map_err(|err| crate::Error::Http(url.clone(), err))? {
+				yield chunk;
+			}
+		}
+	}
 }
 
 /// A single page of a paged response.
@@ -237,4 +339,15 @@ mod tests {
 		println!("{mod_:?}");
 		assert_eq!(mod_.title.0, "Bob's Functions Library mod");
 	}
+
+	#[tokio::test]
+	async fn get_full() {
+		let api = super::Api::new().unwrap();
+
+		let mod_name = factorio_mods_common::ModName("boblibrary".to_owned());
+		let mod_ = api.get_full(&mod_name).await.unwrap();
+		println!("{mod_:?}");
+		assert_eq!(mod_.title.0, "Bob's Functions Library mod");
+		assert!(mod_.description.is_some());
+	}
 }
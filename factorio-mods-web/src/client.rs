@@ -6,7 +6,9 @@ pub(crate) struct Client {
 
 impl Client {
 	/// Creates a new `Client` object.
-	pub(crate) fn new() -> Self {
+	///
+	/// `extra_host` is allowed in addition to the whitelisted hosts, for servers that mirror the mods portal.
+	pub(crate) fn new(extra_host: Option<String>) -> Self {
 		static USER_AGENT: http::HeaderValue = http::HeaderValue::from_static(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")));
 
 		let connector = hyper_tls::HttpsConnector::new();
@@ -16,6 +18,7 @@ impl Client {
 			inner: std::sync::Arc::new(ClientInner {
 				inner,
 				user_agent: USER_AGENT.clone(),
+				extra_host,
 			}),
 		}
 	}
@@ -38,7 +41,7 @@ impl Client {
 				request
 			};
 
-			let (response, url) = inner.send(request, None, &APPLICATION_JSON, url).await?;
+			let (response, url) = inner.send(request, None, &APPLICATION_JSON, url, true).await?;
 			json(response, url).await
 		}
 	}
@@ -60,7 +63,7 @@ impl Client {
 				request
 			};
 
-			let (response, url) = inner.send(request, range, &APPLICATION_ZIP, url).await?;
+			let (response, url) = inner.send(request, range, &APPLICATION_ZIP, url, true).await?;
 			let url = expect_content_type(&response, url, [&*APPLICATION_OCTET_STREAM, &*APPLICATION_ZIP])?;
 			Ok((response, url))
 		}
@@ -81,12 +84,33 @@ impl Client {
 				request
 			};
 
-			let (response, url) = inner.send(request, None, &APPLICATION_ZIP, url).await?;
+			let (response, url) = inner.send(request, None, &APPLICATION_ZIP, url, true).await?;
 			let url = expect_content_type(&response, url, [&*APPLICATION_OCTET_STREAM, &*APPLICATION_ZIP])?;
 			Ok((response, url))
 		}
 	}
 
+	/// GETs the given URL on any host using the given client, and returns the response whatever its content type.
+	///
+	/// This is for files that aren't part of the web API, so it must not be used for requests that contain user credentials.
+	pub(crate) fn get_any(&self, url: url::Url) -> impl std::future::Future<Output = Result<(http::Response<hyper::Body>, url::Url), crate::Error>> {
+		let inner = self.inner.clone();
+
+		async {
+			let request = {
+				let mut request = http::Request::new(Default::default());
+				*request.method_mut() = http::Method::GET;
+				*request.uri_mut() = match url.to_string().parse() {
+					Ok(uri) => uri,
+					Err(err) => return Err(crate::Error::ParseUri(url, err)),
+				};
+				request
+			};
+
+			inner.send(request, None, &ANY, url, false).await
+		}
+	}
+
 	// TODO: Would like to return `impl std::future::Future<Output = Result<(T, url::Url), crate::Error>>`,
 	// but https://github.com/rust-lang/rust/issues/42940 prevents it.
 	/// POSTs the given URL using the given client and request body, and deserializes the response as a JSON object.
@@ -113,7 +137,7 @@ impl Client {
 				request
 			};
 
-			let (response, url) = inner.send(request, None, &APPLICATION_JSON, url).await?;
+			let (response, url) = inner.send(request, None, &APPLICATION_JSON, url, true).await?;
 			json(response, url).await
 		})
 	}
@@ -126,6 +150,9 @@ static WHITELISTED_HOSTS: std::sync::LazyLock<std::collections::BTreeSet<&'stati
 		"mods.factorio.com",
 	].into_iter().collect());
 
+static ANY: std::sync::LazyLock<http::HeaderValue> =
+	std::sync::LazyLock::new(|| http::HeaderValue::from_static("*/*"));
+
 static APPLICATION_JSON: std::sync::LazyLock<http::HeaderValue> =
 	std::sync::LazyLock::new(|| http::HeaderValue::from_static("application/json"));
 
@@ -142,6 +169,7 @@ static WWW_FORM_URL_ENCODED: std::sync::LazyLock<http::HeaderValue> =
 struct ClientInner {
 	inner: hyper::Client<hyper_tls::HttpsConnector<hyper::client::HttpConnector>>,
 	user_agent: http::HeaderValue,
+	extra_host: Option<String>,
 }
 
 impl ClientInner {
@@ -151,8 +179,9 @@ impl ClientInner {
 		range: Option<http::HeaderValue>,
 		accept: &'static http::HeaderValue,
 		url: url::Url,
+		check_host: bool,
 	) -> Result<(http::Response<hyper::Body>, url::Url), crate::Error> {
-		if !matches!(url.host_str(), Some(host) if WHITELISTED_HOSTS.contains(host)) {
+		if check_host && !matches!(url.host_str(), Some(host) if WHITELISTED_HOSTS.contains(host) || self.extra_host.as_deref() == Some(host)) {
 			return Err(crate::Error::NotWhitelistedHost(url));
 		}
 
@@ -198,7 +227,7 @@ impl ClientInner {
 					request
 				};
 
-				Box::pin(self.send(request, range, accept, location)).await
+				Box::pin(self.send(request, range, accept, location, check_host)).await
 			},
 
 			http::StatusCode::UNAUTHORIZED => {
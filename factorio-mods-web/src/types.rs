@@ -3,7 +3,7 @@
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct DateTime(pub String);
 
@@ -11,12 +11,12 @@ pub struct DateTime(pub String);
 #[derive(
 	Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct DownloadCount(pub u64);
 
 /// A mod object returned by [`crate::Api::get`].
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Mod {
 	/// The name of the mod.
 	pub name: factorio_mods_common::ModName,
@@ -36,6 +36,92 @@ pub struct Mod {
 
 	/// The number of times the mod has been downloaded.
 	pub downloads_count: DownloadCount,
+
+	/// A longer description of the mod.
+	#[serde(default)]
+	pub description: Option<factorio_mods_common::ModDescription>,
+
+	/// The category of the mod.
+	#[serde(default)]
+	pub category: Option<ModCategory>,
+
+	/// The tags of the mod.
+	#[serde(default)]
+	pub tags: Vec<ModTag>,
+
+	/// The license of the mod.
+	#[serde(default)]
+	pub license: Option<ModLicense>,
+
+	/// The URL of the homepage of the mod.
+	#[serde(default, deserialize_with = "deserialize_optional_url")]
+	pub homepage: Option<factorio_mods_common::Url>,
+
+	/// The URL of the source code of the mod.
+	#[serde(default, deserialize_with = "deserialize_optional_url")]
+	pub source_url: Option<factorio_mods_common::Url>,
+
+	/// The date and time at which the mod was created.
+	#[serde(default)]
+	pub created_at: Option<DateTime>,
+
+	/// The date and time at which the mod was last updated.
+	#[serde(default)]
+	pub updated_at: Option<DateTime>,
+
+	/// Whether the mod is deprecated.
+	#[serde(default)]
+	pub deprecated: bool,
+}
+
+/// The category of a mod.
+#[derive(
+	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
+	derive_struct::NewTypeDisplay,
+	derive_struct::NewTypeFromStr,
+	serde::Deserialize, serde::Serialize,
+)]
+pub struct ModCategory(pub String);
+
+/// A tag of a mod.
+#[derive(
+	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
+	derive_struct::NewTypeDisplay,
+	derive_struct::NewTypeFromStr,
+	serde::Serialize,
+)]
+pub struct ModTag(pub String);
+
+impl<'de> serde::Deserialize<'de> for ModTag {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		// Older responses of the web API return tags as objects, newer ones return them as strings.
+
+		#[derive(serde::Deserialize)]
+		#[serde(untagged)]
+		enum ModTagInner {
+			Name(String),
+			Object { name: String },
+		}
+
+		match serde::Deserialize::deserialize(deserializer)? {
+			ModTagInner::Name(name) |
+			ModTagInner::Object { name } => Ok(ModTag(name)),
+		}
+	}
+}
+
+/// The license of a mod.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ModLicense {
+	/// The name of the license.
+	pub name: String,
+
+	/// The human-readable title of the license.
+	pub title: String,
+
+	/// The URL of the text of the license.
+	#[serde(default, deserialize_with = "deserialize_optional_url")]
+	pub url: Option<factorio_mods_common::Url>,
 }
 
 /// The summary of a mod.
@@ -43,12 +129,12 @@ pub struct Mod {
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct ModSummary(pub String);
 
 /// A single mod release.
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct ModRelease {
 	/// The version of the mod release.
 	pub version: factorio_mods_common::ReleaseVersion,
@@ -60,7 +146,7 @@ pub struct ModRelease {
 	pub download_url: factorio_mods_common::Url,
 
 	/// The filename of the mod release.
-	#[serde(rename(deserialize = "file_name"))]
+	#[serde(rename = "file_name")]
 	pub filename: Filename,
 
 	/// The date and time at which the mod release was created.
@@ -71,10 +157,16 @@ pub struct ModRelease {
 }
 
 /// Extra information about a single mod release.
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct ModReleaseInfo {
 	/// The versions of the game supported by the mod release.
 	pub factorio_version: factorio_mods_common::ModVersionReq,
+
+	/// The dependencies of the mod release.
+	///
+	/// This is only returned by [`crate::Api::get_full`], not by [`crate::Api::get`] or [`crate::Api::search`]
+	#[serde(default)]
+	pub dependencies: Vec<factorio_mods_common::Dependency>,
 }
 
 /// The hash of a mod release file.
@@ -82,7 +174,7 @@ pub struct ModReleaseInfo {
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct ModHash(pub String);
 
@@ -91,12 +183,12 @@ pub struct ModHash(pub String);
 	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
 	derive_struct::NewTypeDisplay,
 	derive_struct::NewTypeFromStr,
-	serde::Deserialize,
+	serde::Deserialize, serde::Serialize,
 )]
 pub struct Filename(pub String);
 
 /// A mod object returned by [`crate::Api::search`].
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct SearchResponseMod {
 	/// The name of the mod.
 	pub name: factorio_mods_common::ModName,
@@ -117,3 +209,9 @@ pub struct SearchResponseMod {
 	/// The number of times the mod has been downloaded.
 	pub downloads_count: DownloadCount,
 }
+
+/// Deserializes an optional URL, treating an empty string the same as a missing URL.
+fn deserialize_optional_url<'de, D>(deserializer: D) -> Result<Option<factorio_mods_common::Url>, D::Error> where D: serde::Deserializer<'de> {
+	let url: Option<factorio_mods_common::Url> = serde::Deserialize::deserialize(deserializer)?;
+	Ok(url.filter(|url| !url.0.is_empty()))
+}
@@ -119,7 +119,7 @@ pub fn compute_solution<I>(
 						(DependencyKind::Required, true) => requires = true,
 						(DependencyKind::Conflicts, true) |
 						(DependencyKind::Optional, false) => conflicts = true,
-						_ => continue,
+						_ => (),
 					}
 				}
 
@@ -177,7 +177,7 @@ pub fn compute_solution<I>(
 					.filter(|dep| dep.kind() == DependencyKind::Required)
 					.all(|dep|
 						name_to_node_indices.get(dep.name())
-						.map_or(false, |dep_node_indices|
+						.is_some_and(|dep_node_indices|
 							dep_node_indices.iter()
 							.any(|&dep_node_index| dep.version_req().matches(graph[dep_node_index].version()))));
 
@@ -0,0 +1,166 @@
+/// The parsed contents of a mod's `changelog.txt`
+///
+/// Ref: <https://wiki.factorio.com/Tutorial:Mod_changelog_format>
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Changelog {
+	pub(crate) versions: Vec<ChangelogVersion>,
+}
+
+/// The changelog of a single version of a mod.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ChangelogVersion {
+	/// The version as written in the changelog.
+	pub(crate) version_string: String,
+
+	/// The parsed version, if it's a valid version.
+	pub(crate) version: Option<semver::Version>,
+
+	pub(crate) date: Option<String>,
+
+	pub(crate) sections: Vec<ChangelogSection>,
+}
+
+/// A section of the changelog of a single version, like "Features" or "Bugfixes".
+#[derive(Debug, PartialEq)]
+pub(crate) struct ChangelogSection {
+	pub(crate) name: String,
+	pub(crate) entries: Vec<String>,
+}
+
+impl Changelog {
+	/// Parses the contents of a `changelog.txt`
+	///
+	/// The parser is lenient, since the game itself only warns about malformed changelogs. Lines that don't fit the format are ignored.
+	pub(crate) fn parse(s: &str) -> Self {
+		let mut versions: Vec<ChangelogVersion> = vec![];
+
+		for line in s.lines() {
+			let line = line.trim_end();
+
+			if line.is_empty() || line.starts_with("---") {
+				continue;
+			}
+
+			if let Some(version_string) = line.strip_prefix("Version:") {
+				let version_string = version_string.trim().to_owned();
+				let version =
+					version_string.parse().ok()
+					.or_else(|| factorio_mods_common::fixup_version(&version_string).parse().ok());
+				versions.push(ChangelogVersion {
+					version_string,
+					version,
+					date: None,
+					sections: vec![],
+				});
+				continue;
+			}
+
+			let Some(version) = versions.last_mut() else {
+				continue;
+			};
+
+			if let Some(date) = line.strip_prefix("Date:") {
+				version.date = Some(date.trim().to_owned());
+			}
+			else if let Some(entry) = line.trim_start().strip_prefix("- ") {
+				if let Some(section) = version.sections.last_mut() {
+					section.entries.push(entry.trim().to_owned());
+				}
+			}
+			else if line.starts_with("  ") && !line.starts_with("    ") && line.ends_with(':') {
+				let name = line.trim().trim_end_matches(':').to_owned();
+				version.sections.push(ChangelogSection {
+					name,
+					entries: vec![],
+				});
+			}
+			else if line.starts_with("    ") {
+				// Continuation of the previous entry
+				if let Some(entry) = version.sections.last_mut().and_then(|section| section.entries.last_mut()) {
+					entry.push(' ');
+					entry.push_str(line.trim());
+				}
+			}
+		}
+
+		Changelog { versions }
+	}
+
+	/// Prints the changelogs of the versions that match the given predicate.
+	pub(crate) fn print(&self, mut predicate: impl FnMut(&ChangelogVersion) -> bool) {
+		let entry_textwrap_options =
+			crate::textwrap_options()
+			.initial_indent("        - ")
+			.subsequent_indent("          ");
+
+		for version in &self.versions {
+			if !predicate(version) {
+				continue;
+			}
+
+			match &version.date {
+				Some(date) => println!("    Version: {} ({date})", version.version_string),
+				None => println!("    Version: {}", version.version_string),
+			}
+
+			for section in &version.sections {
+				println!("      {}:", section.name);
+				for entry in &section.entries {
+					for line in textwrap::wrap(entry, entry_textwrap_options.clone()) {
+						println!("{line}");
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse() {
+		let changelog = super::Changelog::parse("\
+---------------------------------------------------------------------------------------------------
+Version: 1.1.02
+Date: 2021-01-02
+  Bugfixes:
+    - Fixed a crash
+      when loading a save.
+    - Fixed another crash.
+---------------------------------------------------------------------------------------------------
+Version: 1.1.1
+  Features:
+    - Initial release.
+");
+
+		assert_eq!(changelog, super::Changelog {
+			versions: vec![
+				super::ChangelogVersion {
+					version_string: "1.1.02".to_owned(),
+					version: Some("1.1.2".parse().unwrap()),
+					date: Some("2021-01-02".to_owned()),
+					sections: vec![
+						super::ChangelogSection {
+							name: "Bugfixes".to_owned(),
+							entries: vec![
+								"Fixed a crash when loading a save.".to_owned(),
+								"Fixed another crash.".to_owned(),
+							],
+						},
+					],
+				},
+				super::ChangelogVersion {
+					version_string: "1.1.1".to_owned(),
+					version: Some("1.1.1".parse().unwrap()),
+					date: None,
+					sections: vec![
+						super::ChangelogSection {
+							name: "Features".to_owned(),
+							entries: vec!["Initial release.".to_owned()],
+						},
+					],
+				},
+			],
+		});
+	}
+}
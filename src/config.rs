@@ -5,65 +5,343 @@ use anyhow::Context;
 enum StoredConfig<'a> {
 	V1 {
 		install_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		install: Option<std::borrow::Cow<'a, str>>,
 		user_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		mods_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
 		mods: Option<std::borrow::Cow<'a, std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ModVersionReq>>>,
 	},
+
+	V2 {
+		install_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		install: Option<std::borrow::Cow<'a, str>>,
+		user_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		mods_directory: Option<std::borrow::Cow<'a, std::path::Path>>,
+		mods: Option<std::borrow::Cow<'a, std::collections::BTreeMap<factorio_mods_common::ModName, ModEntry>>>,
+		#[serde(default, skip_serializing_if = "is_default")]
+		holds: std::borrow::Cow<'a, std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>>,
+		#[serde(default, skip_serializing_if = "is_default")]
+		include: std::borrow::Cow<'a, [std::path::PathBuf]>,
+		#[serde(default, skip_serializing_if = "is_default")]
+		on_conflict: OnConflict,
+		#[serde(default, skip_serializing_if = "is_default")]
+		repositories: std::borrow::Cow<'a, std::collections::BTreeMap<String, Repository>>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		portal_url: Option<std::borrow::Cow<'a, str>>,
+	},
+}
+
+/// The version of the config file format.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) enum ConfigVersion {
+	V1,
+	V2,
 }
 
 #[derive(Debug)]
 pub(crate) struct Config {
 	path: std::path::PathBuf,
 
+	/// The format that the config file was loaded from, which it is also saved in unless it needs a newer one.
+	pub version: ConfigVersion,
+
 	pub install_directory: Option<std::path::PathBuf>,
+	pub install: Option<String>,
 	pub user_directory: Option<std::path::PathBuf>,
-	pub mods: Option<std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ModVersionReq>>,
+	pub mods_directory: Option<std::path::PathBuf>,
+	pub mods: Option<std::collections::BTreeMap<factorio_mods_common::ModName, ModEntry>>,
+
+	/// Mods that are held at the given version, whether they're in `mods` or only installed as dependencies.
+	pub holds: std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+
+	/// Other config files whose `mods` and `holds` are merged into this one's, in order, before this one's own.
+	pub include: Vec<std::path::PathBuf>,
+
+	/// How to merge requirements for the same mod from different config files.
+	pub on_conflict: OnConflict,
+
+	/// Mod repositories that mods can be installed from besides the mods portal, by name.
+	pub repositories: std::collections::BTreeMap<String, Repository>,
+
+	/// The base URL of a server to use instead of the mods portal, like one run by `fac serve`.
+	pub portal_url: Option<String>,
+
+	/// The merged `mods` and `holds` of the included config files, so that they aren't saved into this one.
+	included: Layer,
+
+	/// The names of the mods and holds that this config file has itself, which are saved even if they're the same as in the included config files.
+	own_mods: std::collections::BTreeSet<factorio_mods_common::ModName>,
+	own_holds: std::collections::BTreeSet<factorio_mods_common::ModName>,
 }
 
-impl Config {
-	pub fn load(path: Option<std::path::PathBuf>) -> anyhow::Result<Self> {
-		static FACTORIO_INSTALL_SEARCH_PATHS: std::sync::LazyLock<Vec<std::path::PathBuf>> =
-			std::sync::LazyLock::new(|| {
-				let mut result = vec![];
+/// A mod repository, ie a directory or a static HTTP tree of mod zip files with an index file generated by `fac repo index`
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub(crate) struct Repository {
+	/// The path of the directory, relative to the config file, or a `file://`, `http://` or `https://` URL.
+	pub url: String,
 
-				if cfg!(windows) {
-					if let Some(path) = std::env::var_os("ProgramW6432") {
-						let mut path: std::path::PathBuf = path.into();
-						path.push("Steam");
-						path.push("steamapps");
-						path.push("common");
-						path.push("Factorio");
-						result.push(path);
-					}
-					if let Some(path) = std::env::var_os("ProgramFiles") {
-						let mut path: std::path::PathBuf = path.into();
-						path.push("Steam");
-						path.push("steamapps");
-						path.push("common");
-						path.push("Factorio");
-						result.push(path);
-					}
+	/// For each mod, sources are asked in order of priority and the first one that has the mod is used.
+	/// The mods portal has priority 0.
+	#[serde(default = "default_repository_priority")]
+	pub priority: i32,
+}
+
+fn default_repository_priority() -> i32 {
+	1
+}
+
+/// How to merge requirements for the same mod from different config files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OnConflict {
+	/// Fail to load the config.
+	#[default]
+	Error,
+
+	/// Require the mod to satisfy all the requirements.
+	Intersect,
+}
+
+/// The parts of a config file that are merged with the config files that it includes.
+#[derive(Clone, Debug, Default)]
+struct Layer {
+	mods: Option<std::collections::BTreeMap<factorio_mods_common::ModName, ModEntry>>,
+	holds: std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+}
+
+impl Layer {
+	/// Merges the given layer on top of this one.
+	///
+	/// Requirements for the same mod conflict unless they're equal or one of them is `*`.
+	/// Other fields of the mod's entry and holds of the same mod are taken from the given layer.
+	fn merge(&mut self, other: Layer, on_conflict: OnConflict, other_path: &std::path::Path) -> anyhow::Result<()> {
+		if let Some(other_mods) = other.mods {
+			let mods = self.mods.get_or_insert_with(Default::default);
+
+			for (name, other_entry) in other_mods {
+				let entry = match mods.entry(name.clone()) {
+					std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+					std::collections::btree_map::Entry::Vacant(entry) => {
+						entry.insert(other_entry);
+						continue;
+					},
+				};
+
+				if entry.version.0 == semver::VersionReq::STAR {
+					entry.version = other_entry.version;
 				}
-				else {
-					if let Some(mut path) = dirs::home_dir() {
-						path.push(".steam");
-						path.push("steam");
-						path.push("steamapps");
-						path.push("common");
-						path.push("Factorio");
-						result.push(path);
-					}
+				else if other_entry.version != entry.version && other_entry.version.0 != semver::VersionReq::STAR {
+					match on_conflict {
+						OnConflict::Error => anyhow::bail!(
+							r#"mod {} is required as "{}" by an included config file but as "{}" by {}. Set "on_conflict": "intersect" to require both."#,
+							name, entry.version, other_entry.version, other_path.display(),
+						),
 
-					if let Some(mut path) = dirs::data_dir() {
-						path.push("Steam");
-						path.push("steamapps");
-						path.push("common");
-						path.push("Factorio");
-						result.push(path);
+						OnConflict::Intersect => entry.version.0.comparators.extend(other_entry.version.0.comparators),
 					}
 				}
 
-				result
-			});
+				if other_entry.enabled.is_some() {
+					entry.enabled = other_entry.enabled;
+				}
+				if other_entry.optional_dependencies != OptionalDependencies::default() {
+					entry.optional_dependencies = other_entry.optional_dependencies;
+				}
+				if other_entry.note.is_some() {
+					entry.note = other_entry.note;
+				}
+				// An entry has either a source or a git repository, so setting one replaces the other.
+				if other_entry.source.is_some() {
+					entry.source = other_entry.source;
+					entry.git = None;
+				}
+				if other_entry.git.is_some() {
+					entry.git = other_entry.git;
+					entry.source = None;
+				}
+			}
+		}
+
+		self.holds.extend(other.holds);
+
+		Ok(())
+	}
+}
+
+/// A mod in the config file.
+///
+/// In the config file, an entry that only has a version requirement can also be written as just the requirement.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "StoredModEntry", into = "StoredModEntry")]
+pub(crate) struct ModEntry {
+	/// The versions of the mod that may be installed.
+	pub version: factorio_mods_common::ModVersionReq,
+
+	/// Whether the mod should be enabled or disabled in `mod-list.json` after installing. `None` leaves it as it is.
+	pub enabled: Option<bool>,
+
+	/// Which of the mod's optional dependencies should also be installed.
+	pub optional_dependencies: OptionalDependencies,
+
+	/// A free-form note, such as why the mod is installed or pinned.
+	pub note: Option<String>,
+
+	/// Where to get the mod from, instead of the mods portal.
+	pub source: Option<String>,
+
+	/// The git repository to build the mod from, instead of getting it from a source.
+	pub git: Option<GitSource>,
+}
+
+/// A mod in a git repository.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct GitSource {
+	/// The URL of the repository, in any form that `git clone` accepts.
+	pub url: String,
+
+	/// The branch, tag or commit to build the mod from. `None` uses the repository's default branch.
+	pub rev: Option<String>,
+
+	/// The directory of the mod's `info.json` in the repository, separated by `/`. `None` is the root of the repository.
+	pub subdir: Option<String>,
+}
+
+impl ModEntry {
+	/// Returns true if the entry has nothing but a version requirement.
+	fn is_version_only(&self) -> bool {
+		self.enabled.is_none() &&
+		self.optional_dependencies == OptionalDependencies::default() &&
+		self.note.is_none() &&
+		self.source.is_none() &&
+		self.git.is_none()
+	}
+}
+
+impl From<factorio_mods_common::ModVersionReq> for ModEntry {
+	fn from(version: factorio_mods_common::ModVersionReq) -> Self {
+		ModEntry {
+			version,
+			enabled: None,
+			optional_dependencies: Default::default(),
+			note: None,
+			source: None,
+			git: None,
+		}
+	}
+}
+
+/// Which optional dependencies of a mod should also be installed. Either all or none of them, or a list of names.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub(crate) enum OptionalDependencies {
+	All(bool),
+	Only(std::collections::BTreeSet<factorio_mods_common::ModName>),
+}
+
+impl OptionalDependencies {
+	/// Returns true if the optional dependency with the given name should be installed.
+	pub(crate) fn includes(&self, name: &factorio_mods_common::ModName) -> bool {
+		match self {
+			OptionalDependencies::All(all) => *all,
+			OptionalDependencies::Only(names) => names.contains(name),
+		}
+	}
+}
+
+impl Default for OptionalDependencies {
+	fn default() -> Self {
+		OptionalDependencies::All(false)
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum StoredModEntry {
+	Version(factorio_mods_common::ModVersionReq),
+
+	Entry {
+		#[serde(default = "version_req_star")]
+		version: factorio_mods_common::ModVersionReq,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		enabled: Option<bool>,
+		#[serde(default, skip_serializing_if = "is_default")]
+		optional_dependencies: OptionalDependencies,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		note: Option<String>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		source: Option<String>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		git: Option<String>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		rev: Option<String>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		subdir: Option<String>,
+	},
+}
+
+impl TryFrom<StoredModEntry> for ModEntry {
+	type Error = &'static str;
+
+	fn try_from(entry: StoredModEntry) -> Result<Self, Self::Error> {
+		match entry {
+			StoredModEntry::Version(version) => Ok(version.into()),
+
+			StoredModEntry::Entry { version, enabled, optional_dependencies, note, source, git, rev, subdir } => {
+				let git = match (git, rev, subdir) {
+					(Some(url), rev, subdir) => {
+						if source.is_some() {
+							return Err(r#""git" and "source" can't both be set"#);
+						}
+						// git would parse such a URL as an option.
+						if url.starts_with('-') {
+							return Err(r#""git" can't start with "-""#);
+						}
+						Some(GitSource { url, rev, subdir })
+					},
+					(None, None, None) => None,
+					(None, _, _) => return Err(r#""rev" and "subdir" need "git" to be set"#),
+				};
+
+				Ok(ModEntry { version, enabled, optional_dependencies, note, source, git })
+			},
+		}
+	}
+}
+
+impl From<ModEntry> for StoredModEntry {
+	fn from(entry: ModEntry) -> Self {
+		if entry.is_version_only() {
+			StoredModEntry::Version(entry.version)
+		}
+		else {
+			let ModEntry { version, enabled, optional_dependencies, note, source, git } = entry;
+			let (git, rev, subdir) = match git {
+				Some(GitSource { url, rev, subdir }) => (Some(url), rev, subdir),
+				None => (None, None, None),
+			};
+			StoredModEntry::Entry { version, enabled, optional_dependencies, note, source, git, rev, subdir }
+		}
+	}
+}
+
+fn version_req_star() -> factorio_mods_common::ModVersionReq {
+	factorio_mods_common::ModVersionReq(semver::VersionReq::STAR)
+}
+
+fn is_default<T>(value: &T) -> bool where T: Default + PartialEq {
+	*value == T::default()
+}
+
+impl Config {
+	/// Loads the config file at the given path, and auto-detects the directories that it doesn't set.
+	///
+	/// `install_directory_override` is the install directory given on the command line or in the environment, if any.
+	/// Installs aren't searched for if it's given. Neither it nor the user directory of its install are stored in the config,
+	/// so that they aren't saved to the config file.
+	pub fn load(path: Option<std::path::PathBuf>, install_directory_override: Option<&std::path::Path>) -> anyhow::Result<Self> {
 		static FACTORIO_USER_SEARCH_PATHS: std::sync::LazyLock<Vec<std::path::PathBuf>> =
 			std::sync::LazyLock::new(|| {
 				let mut result = vec![];
@@ -84,15 +362,7 @@ impl Config {
 
 		let config_file_path =
 			if let Some(path) = path {
-				if path.iter().count() == 1 {
-					let mut user_config_dir = dirs::config_dir().context("could not derive path to config directory")?;
-					user_config_dir.push("fac");
-					user_config_dir.push(path);
-					user_config_dir
-				}
-				else {
-					path
-				}
+				resolve_config_file_path(&path, std::path::Path::new(""))?
 			}
 			else {
 				let mut user_config_dir = dirs::config_dir().context("could not derive path to config directory")?;
@@ -104,40 +374,80 @@ impl Config {
 
 		let config_file_path_displayable = config_file_path.display();
 
-		let (install_directory, user_directory, mods) = match std::fs::File::open(&config_file_path) {
+		let (version, install_directory, install, user_directory, mods_directory, own, include, on_conflict, repositories, portal_url) = match std::fs::File::open(&config_file_path) {
 			Ok(mut file) => {
 				let config: StoredConfig<'_> =
 					serde_json::from_reader(&mut file)
 					.with_context(|| format!("could not parse JSON file {config_file_path_displayable}"))?;
 
-				let StoredConfig::V1 { install_directory, user_directory, mods } = config;
+				match config {
+					StoredConfig::V1 { install_directory, install, user_directory, mods_directory, mods } => (
+						ConfigVersion::V1,
+						install_directory.map(std::borrow::Cow::into_owned),
+						install.map(std::borrow::Cow::into_owned),
+						user_directory.map(std::borrow::Cow::into_owned),
+						mods_directory.map(std::borrow::Cow::into_owned),
+						Layer {
+							mods: mods.map(|mods| mods.into_owned().into_iter().map(|(name, version)| (name, version.into())).collect()),
+							holds: Default::default(),
+						},
+						vec![],
+						OnConflict::default(),
+						Default::default(),
+						None,
+					),
 
-				(
-					install_directory.map(std::borrow::Cow::into_owned),
-					user_directory.map(std::borrow::Cow::into_owned),
-					mods.map(std::borrow::Cow::into_owned),
-				)
+					StoredConfig::V2 { install_directory, install, user_directory, mods_directory, mods, holds, include, on_conflict, repositories, portal_url } => (
+						ConfigVersion::V2,
+						install_directory.map(std::borrow::Cow::into_owned),
+						install.map(std::borrow::Cow::into_owned),
+						user_directory.map(std::borrow::Cow::into_owned),
+						mods_directory.map(std::borrow::Cow::into_owned),
+						Layer {
+							mods: mods.map(std::borrow::Cow::into_owned),
+							holds: holds.into_owned(),
+						},
+						include.into_owned(),
+						on_conflict,
+						repositories.into_owned(),
+						portal_url.map(std::borrow::Cow::into_owned),
+					),
+				}
 			},
 
-			Err(err) if err.kind() == std::io::ErrorKind::NotFound => (None, None, None),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound =>
+				(ConfigVersion::V2, None, None, None, None, Layer::default(), vec![], OnConflict::default(), Default::default(), None),
 
 			Err(err) => return Err(anyhow::Error::new(err).context(format!("could not read config file {config_file_path_displayable}"))),
 		};
 
+		let mut stack = vec![std::fs::canonicalize(&config_file_path).unwrap_or_else(|_| config_file_path.clone())];
+		let included = load_includes(&config_file_path, &include, on_conflict, &mut stack)?;
+
+		let own_mods = own.mods.iter().flat_map(std::collections::BTreeMap::keys).cloned().collect();
+		let own_holds = own.holds.keys().cloned().collect();
+
+		let mut resolved = included.clone();
+		resolved.merge(own, on_conflict, &config_file_path)?;
+		let Layer { mods, holds } = resolved;
+
+		// An install directory given on the command line or in the environment makes searching unnecessary.
+		// Otherwise an install chosen by name takes precedence over the install directory, since the directory is saved after auto-detection.
 		let install_directory =
-			install_directory.or_else(|| FACTORIO_INSTALL_SEARCH_PATHS.iter().find_map(|search_path| {
-				let search_path = std::path::Path::new(search_path);
-				let base_info_file_path = search_path.join("data").join("base").join("info.json");
-				if base_info_file_path.is_file() {
-					Some(search_path.to_owned())
-				}
-				else {
-					None
-				}
-			}));
+			if install_directory_override.is_some() {
+				install_directory
+			}
+			else if let Some(install) = &install {
+				crate::installs::find().into_iter().find(|found| found.name == *install).map(|found| found.path)
+			}
+			else {
+				install_directory.or_else(|| crate::installs::find().into_iter().next().map(|found| found.path))
+			};
 
 		let user_directory =
-			user_directory.or_else(|| FACTORIO_USER_SEARCH_PATHS.iter().find_map(|search_path| {
+			user_directory
+			.or_else(|| install_directory.as_deref().and_then(user_directory_of_install))
+			.or_else(|| FACTORIO_USER_SEARCH_PATHS.iter().find_map(|search_path| {
 				let search_path = std::path::Path::new(search_path);
 
 				let mods_directory = search_path.join("mods");
@@ -154,26 +464,525 @@ impl Config {
 
 		Ok(Config {
 			path: config_file_path,
+			version,
 			install_directory,
+			install,
 			user_directory,
+			mods_directory,
 			mods,
+			holds,
+			include,
+			on_conflict,
+			repositories,
+			portal_url,
+			included,
+			own_mods,
+			own_holds,
 		})
 	}
 
+	/// Saves the config file in the format it was loaded from, or in the newest format if anything needs it.
+	///
+	/// Mods and holds that are the same as in the included config files are not saved into this one, unless this one already had them.
 	pub fn save(&self) -> anyhow::Result<()> {
 		let config_file_path_displayable = self.path.display();
+
+		let mods = self.mods.as_ref().map(|mods| {
+			for name in self.included.mods.iter().flat_map(std::collections::BTreeMap::keys) {
+				if !mods.contains_key(name) {
+					eprintln!("Mod {name} is still required by a config file that {config_file_path_displayable} includes.");
+				}
+			}
+
+			mods.iter()
+			.filter(|&(name, entry)| self.own_mods.contains(name) || self.included.mods.as_ref().and_then(|included_mods| included_mods.get(name)) != Some(entry))
+			.map(|(name, entry)| (name.clone(), entry.clone()))
+			.collect()
+		});
+
+		for (name, version) in &self.included.holds {
+			if !self.holds.contains_key(name) {
+				eprintln!("Mod {name} is still held at {version} by a config file that {config_file_path_displayable} includes.");
+			}
+		}
+
+		let holds =
+			self.holds.iter()
+			.filter(|&(name, version)| self.own_holds.contains(name) || self.included.holds.get(name) != Some(version))
+			.map(|(name, version)| (name.clone(), version.clone()))
+			.collect();
+
+		let stored_config = self.to_stored(Layer { mods, holds }, &self.include, self.on_conflict);
+
 		let mut config_file =
 			std::fs::File::create(&self.path)
 			.with_context(|| format!("could not create config file {config_file_path_displayable}"))?;
-
-		let stored_config = StoredConfig::V1 {
-			install_directory: self.install_directory.as_ref().map(AsRef::as_ref).map(std::borrow::Cow::Borrowed),
-			user_directory: self.user_directory.as_ref().map(AsRef::as_ref).map(std::borrow::Cow::Borrowed),
-			mods: self.mods.as_ref().map(std::borrow::Cow::Borrowed),
-		};
 		serde_json::to_writer_pretty(&mut config_file, &stored_config)
 		.with_context(|| format!("could not write to config file {config_file_path_displayable}"))?;
 
 		Ok(())
 	}
+
+	/// Returns the directory of the config file, which relative paths in the config file are relative to.
+	pub(crate) fn directory(&self) -> &std::path::Path {
+		self.path.parent().unwrap_or_else(|| std::path::Path::new(""))
+	}
+
+	fn to_stored<'a>(&'a self, layer: Layer, include: &'a [std::path::PathBuf], on_conflict: OnConflict) -> StoredConfig<'a> {
+		// An install chosen by name is found again every time, in case it moves.
+		let install_directory =
+			if self.install.is_some() { None }
+			else { self.install_directory.as_ref().map(AsRef::as_ref).map(std::borrow::Cow::Borrowed) };
+		let install = self.install.as_deref().map(std::borrow::Cow::Borrowed);
+		let user_directory = self.user_directory.as_ref().map(AsRef::as_ref).map(std::borrow::Cow::Borrowed);
+		let mods_directory = self.mods_directory.as_ref().map(AsRef::as_ref).map(std::borrow::Cow::Borrowed);
+
+		let Layer { mods, holds } = layer;
+
+		let needs_v2 =
+			mods.iter().flat_map(std::collections::BTreeMap::values).any(|entry| !entry.is_version_only()) ||
+			!holds.is_empty() ||
+			!include.is_empty() ||
+			on_conflict != OnConflict::default() ||
+			!self.repositories.is_empty() ||
+			self.portal_url.is_some();
+
+		if self.version == ConfigVersion::V1 && !needs_v2 {
+			StoredConfig::V1 {
+				install_directory,
+				install,
+				user_directory,
+				mods_directory,
+				mods: mods.map(|mods|
+					std::borrow::Cow::Owned(mods.into_iter().map(|(name, entry)| (name, entry.version)).collect())),
+			}
+		}
+		else {
+			StoredConfig::V2 {
+				install_directory,
+				install,
+				user_directory,
+				mods_directory,
+				mods: mods.map(std::borrow::Cow::Owned),
+				holds: std::borrow::Cow::Owned(holds),
+				include: std::borrow::Cow::Borrowed(include),
+				on_conflict,
+				repositories: std::borrow::Cow::Borrowed(&self.repositories),
+				portal_url: self.portal_url.as_deref().map(std::borrow::Cow::Borrowed),
+			}
+		}
+	}
+}
+
+/// Resolves the path of a config file given on the command line or included by another config file.
+///
+/// Bare file names are in `fac`'s own config directory. Other relative paths are relative to `relative_to`.
+fn resolve_config_file_path(path: &std::path::Path, relative_to: &std::path::Path) -> anyhow::Result<std::path::PathBuf> {
+	if path.iter().count() == 1 {
+		let mut user_config_dir = dirs::config_dir().context("could not derive path to config directory")?;
+		user_config_dir.push("fac");
+		user_config_dir.push(path);
+		Ok(user_config_dir)
+	}
+	else {
+		Ok(relative_to.join(path))
+	}
+}
+
+/// Loads and merges the given config files included by the config file at the given path.
+///
+/// `stack` has the canonicalized paths of the config files that are being loaded, to detect include cycles.
+fn load_includes(
+	including_file_path: &std::path::Path,
+	include: &[std::path::PathBuf],
+	on_conflict: OnConflict,
+	stack: &mut Vec<std::path::PathBuf>,
+) -> anyhow::Result<Layer> {
+	let mut result = Layer::default();
+
+	let relative_to = including_file_path.parent().unwrap_or(std::path::Path::new(""));
+
+	for path in include {
+		let path = resolve_config_file_path(path, relative_to)?;
+		let path_displayable = path.display();
+
+		let canonicalized_path =
+			std::fs::canonicalize(&path)
+			.with_context(|| format!("could not read config file {path_displayable} included by {}", including_file_path.display()))?;
+		if stack.contains(&canonicalized_path) {
+			anyhow::bail!("config file {path_displayable} includes itself");
+		}
+
+		let file = std::fs::File::open(&path).with_context(|| format!("could not read config file {path_displayable}"))?;
+		let config: StoredConfig<'_> =
+			serde_json::from_reader(std::io::BufReader::new(file))
+			.with_context(|| format!("could not parse JSON file {path_displayable}"))?;
+
+		// Only the mods and holds of included config files are used.
+		let (layer, include) = match config {
+			StoredConfig::V1 { mods, .. } => (
+				Layer {
+					mods: mods.map(|mods| mods.into_owned().into_iter().map(|(name, version)| (name, version.into())).collect()),
+					holds: Default::default(),
+				},
+				vec![],
+			),
+
+			StoredConfig::V2 { mods, holds, include, .. } => (
+				Layer {
+					mods: mods.map(std::borrow::Cow::into_owned),
+					holds: holds.into_owned(),
+				},
+				include.into_owned(),
+			),
+		};
+
+		stack.push(canonicalized_path);
+		let mut included = load_includes(&path, &include, on_conflict, stack)?;
+		stack.pop();
+
+		included.merge(layer, on_conflict, &path)?;
+		result.merge(included, on_conflict, &path)?;
+	}
+
+	Ok(result)
+}
+
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[command(subcommand)]
+	subcommand: ConfigSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum ConfigSubCommand {
+	#[command(name = "migrate", about = "Upgrade the config file to the newest format")]
+	Migrate,
+
+	#[command(name = "show", about = "Show the config file")]
+	Show {
+		#[arg(help = "Show the effective config, with included config files merged in and auto-detected directories filled in", long = "resolved")]
+		resolved: bool,
+	},
+}
+
+impl SubCommand {
+	pub(crate) fn run(
+		self,
+		mut config: Config,
+	) -> anyhow::Result<()> {
+		match self.subcommand {
+			ConfigSubCommand::Migrate => {
+				if config.version == ConfigVersion::V2 && config.path.is_file() {
+					println!("Config file {} already uses the newest format.", config.path.display());
+					return Ok(());
+				}
+
+				config.version = ConfigVersion::V2;
+				config.save()?;
+
+				println!("Config file {} now uses format V2.", config.path.display());
+			},
+
+			ConfigSubCommand::Show { resolved: false } => {
+				let config_file_path_displayable = config.path.display();
+				match std::fs::read_to_string(&config.path) {
+					Ok(contents) => println!("{contents}"),
+					Err(err) if err.kind() == std::io::ErrorKind::NotFound => println!("Config file {config_file_path_displayable} does not exist yet."),
+					Err(err) => return Err(anyhow::Error::new(err).context(format!("could not read config file {config_file_path_displayable}"))),
+				}
+			},
+
+			ConfigSubCommand::Show { resolved: true } => {
+				let layer = Layer { mods: config.mods.clone(), holds: config.holds.clone() };
+				let stored_config = config.to_stored(layer, &[], OnConflict::default());
+				println!("{}", serde_json::to_string_pretty(&stored_config).context("could not serialize config")?);
+			},
+		}
+
+		Ok(())
+	}
+}
+
+/// Finds the user directory that the game at the given install directory uses, the same way the game does.
+///
+/// The game reads `config-path.cfg` next to its `bin` directory to find its config directory, then reads the `write-data` path
+/// from `config.ini` in that directory. If `use-system-read-write-data-directories` is set, the user directory is the system one
+/// unless `config.ini` says otherwise. Standalone installs use paths relative to the executable so that their data stays next to it.
+///
+/// Ref: <https://wiki.factorio.com/Application_directory>
+pub(crate) fn user_directory_of_install(install_directory: &std::path::Path) -> Option<std::path::PathBuf> {
+	let config_path_cfg = std::fs::read_to_string(install_directory.join("config-path.cfg")).ok()?;
+	let config_path_cfg = parse_ini(&config_path_cfg);
+	let config_path_cfg = config_path_cfg.get("")?;
+
+	let use_system_directories = config_path_cfg.get("use-system-read-write-data-directories").is_none_or(|value| *value == "true");
+
+	let write_data =
+		config_path_cfg.get("config-path")
+		.and_then(|config_path| resolve_path(config_path, install_directory))
+		.and_then(|config_path| std::fs::read_to_string(config_path.join("config.ini")).ok())
+		.and_then(|config_ini| {
+			let config_ini = parse_ini(&config_ini);
+			let write_data = config_ini.get("path")?.get("write-data")?;
+			resolve_path(write_data, install_directory)
+		});
+
+	let user_directory =
+		if let Some(write_data) = write_data {
+			write_data
+		}
+		else if use_system_directories {
+			system_write_data_directory()?
+		}
+		else {
+			return None;
+		};
+
+	user_directory.is_dir().then_some(user_directory)
+}
+
+/// Parses the sections and `key=value` pairs of an INI file. Keys before the first section are in the section with the empty name.
+fn parse_ini(s: &str) -> std::collections::BTreeMap<&str, std::collections::BTreeMap<&str, &str>> {
+	let mut result: std::collections::BTreeMap<_, std::collections::BTreeMap<_, _>> = Default::default();
+
+	let mut section = "";
+
+	for line in s.lines() {
+		let line = line.trim();
+
+		if line.is_empty() || line.starts_with([';', '#']) {
+			continue;
+		}
+
+		if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+			section = name.trim();
+		}
+		else if let Some((key, value)) = line.split_once('=') {
+			result.entry(section).or_default().insert(key.trim(), value.trim());
+		}
+	}
+
+	result
+}
+
+/// Resolves the `__PATH__...__` placeholders that the game's config files use in paths.
+///
+/// Returns `None` if the path has an unknown placeholder.
+fn resolve_path(path: &str, install_directory: &std::path::Path) -> Option<std::path::PathBuf> {
+	let (base, rest) =
+		if let Some(rest) = path.strip_prefix("__PATH__executable__") {
+			let executable_directory =
+				if cfg!(target_os = "macos") {
+					install_directory.join("Contents").join("MacOS")
+				}
+				else {
+					install_directory.join("bin").join("x64")
+				};
+			(executable_directory, rest.trim_start_matches(['/', '\\']))
+		}
+		else if let Some(rest) = path.strip_prefix("__PATH__system-write-data__") {
+			(system_write_data_directory()?, rest.trim_start_matches(['/', '\\']))
+		}
+		else if path.starts_with("__PATH__") {
+			return None;
+		}
+		else {
+			(std::path::PathBuf::new(), path)
+		};
+
+	let mut result = base;
+
+	for component in std::path::Path::new(rest).components() {
+		match component {
+			std::path::Component::ParentDir => { result.pop(); },
+			std::path::Component::CurDir => (),
+			component => result.push(component),
+		}
+	}
+
+	Some(result)
+}
+
+/// The directory that the game uses for its user data when `use-system-read-write-data-directories` is set.
+fn system_write_data_directory() -> Option<std::path::PathBuf> {
+	if cfg!(windows) {
+		let mut path = dirs::data_dir()?;
+		path.push("Factorio");
+		Some(path)
+	}
+	else if cfg!(target_os = "macos") {
+		let mut path = dirs::data_dir()?;
+		path.push("factorio");
+		Some(path)
+	}
+	else {
+		let mut path = dirs::home_dir()?;
+		path.push(".factorio");
+		Some(path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_mod_entry() {
+		let mods: std::collections::BTreeMap<factorio_mods_common::ModName, super::ModEntry> = serde_json::from_str(r#"{
+			"foo": "^1.2",
+			"bar": { "enabled": false, "optional_dependencies": ["baz"], "note": "breaks saves after 2.0" },
+			"baz": { "version": "=0.3.0", "optional_dependencies": true }
+		}"#).unwrap();
+
+		let foo = &mods[&factorio_mods_common::ModName("foo".to_owned())];
+		assert_eq!(foo.version.0, "^1.2".parse().unwrap());
+		assert!(foo.is_version_only());
+
+		let bar = &mods[&factorio_mods_common::ModName("bar".to_owned())];
+		assert_eq!(bar.version.0, semver::VersionReq::STAR);
+		assert_eq!(bar.enabled, Some(false));
+		assert!(bar.optional_dependencies.includes(&factorio_mods_common::ModName("baz".to_owned())));
+		assert!(!bar.optional_dependencies.includes(&factorio_mods_common::ModName("qux".to_owned())));
+		assert_eq!(bar.note.as_deref(), Some("breaks saves after 2.0"));
+
+		let baz = &mods[&factorio_mods_common::ModName("baz".to_owned())];
+		assert_eq!(baz.optional_dependencies, super::OptionalDependencies::All(true));
+
+		let serialized = serde_json::to_value(&mods).unwrap();
+		assert_eq!(serialized["foo"], "^1.2");
+		assert_eq!(serialized["bar"]["enabled"], false);
+		assert!(serialized["bar"].get("source").is_none());
+
+		let roundtripped: std::collections::BTreeMap<factorio_mods_common::ModName, super::ModEntry> = serde_json::from_value(serialized).unwrap();
+		assert_eq!(roundtripped, mods);
+	}
+
+	#[test]
+	fn test_git_mod_entry() {
+		let mods: std::collections::BTreeMap<factorio_mods_common::ModName, super::ModEntry> = serde_json::from_str(r#"{
+			"foo": { "git": "https://example.com/foo.git", "rev": "fix-crash", "subdir": "mod" },
+			"bar": { "git": "https://example.com/bar.git" }
+		}"#).unwrap();
+
+		let foo = mods[&factorio_mods_common::ModName("foo".to_owned())].git.as_ref().unwrap();
+		assert_eq!(foo.url, "https://example.com/foo.git");
+		assert_eq!(foo.rev.as_deref(), Some("fix-crash"));
+		assert_eq!(foo.subdir.as_deref(), Some("mod"));
+
+		let serialized = serde_json::to_value(&mods).unwrap();
+		assert_eq!(serialized["bar"], serde_json::json!({ "version": "*", "git": "https://example.com/bar.git" }));
+
+		let roundtripped: std::collections::BTreeMap<factorio_mods_common::ModName, super::ModEntry> = serde_json::from_value(serialized).unwrap();
+		assert_eq!(roundtripped, mods);
+
+		assert!(serde_json::from_str::<super::ModEntry>(r#"{ "rev": "main" }"#).is_err());
+		assert!(serde_json::from_str::<super::ModEntry>(r#"{ "git": "https://example.com/foo.git", "source": "portal" }"#).is_err());
+		assert!(serde_json::from_str::<super::ModEntry>(r#"{ "git": "--upload-pack=touch /tmp/pwned" }"#).is_err());
+	}
+
+	#[test]
+	fn test_merge_layers() {
+		let layer = |mods: &str| super::Layer { mods: Some(serde_json::from_str(mods).unwrap()), holds: Default::default() };
+		let path = std::path::Path::new("config.json");
+
+		let mut base = layer(r#"{ "foo": "^1.2", "bar": "*", "baz": { "version": ">=0.5", "note": "base" } }"#);
+		base.merge(layer(r#"{ "foo": "*", "bar": "=2.0.0", "qux": "*" }"#), super::OnConflict::Error, path).unwrap();
+		let mods = base.mods.as_ref().unwrap();
+		assert_eq!(mods[&factorio_mods_common::ModName("foo".to_owned())].version.0, "^1.2".parse().unwrap());
+		assert_eq!(mods[&factorio_mods_common::ModName("bar".to_owned())].version.0, "=2.0.0".parse().unwrap());
+		assert!(mods.contains_key(&factorio_mods_common::ModName("qux".to_owned())));
+
+		let err = base.clone().merge(layer(r#"{ "baz": "<0.7" }"#), super::OnConflict::Error, path).unwrap_err();
+		assert!(err.to_string().contains("mod baz"));
+
+		base.merge(layer(r#"{ "baz": { "version": "<0.7", "enabled": false } }"#), super::OnConflict::Intersect, path).unwrap();
+		let baz = &base.mods.as_ref().unwrap()[&factorio_mods_common::ModName("baz".to_owned())];
+		assert_eq!(baz.version.0, ">=0.5, <0.7".parse().unwrap());
+		assert_eq!(baz.enabled, Some(false));
+		assert_eq!(baz.note.as_deref(), Some("base"));
+	}
+
+	#[test]
+	fn test_save_with_includes() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+
+		std::fs::write(directory.join("shared.json"), r#"{ "version": "V2", "mods": { "foo": "^1.0", "bar": "*", "baz": "*" }, "holds": { "foo": "1.2.0" } }"#).unwrap();
+		std::fs::write(
+			directory.join("config.json"),
+			r#"{ "version": "V2", "install_directory": "install", "user_directory": "user", "include": ["./shared.json"], "mods": { "foo": "^1.0", "qux": "*" }, "holds": { "foo": "1.2.0" } }"#,
+		).unwrap();
+
+		let mut config = super::Config::load(Some(directory.join("config.json")), None).unwrap();
+		let mods = config.mods.as_mut().unwrap();
+		assert_eq!(mods.keys().map(|name| &*name.0).collect::<Vec<_>>(), ["bar", "baz", "foo", "qux"]);
+
+		// baz is added with the same requirement as the included file, and bar gets a different one.
+		mods.insert(factorio_mods_common::ModName("bar".to_owned()), factorio_mods_common::ModVersionReq("^2.0".parse().unwrap()).into());
+		config.save().unwrap();
+
+		// foo and its hold are kept even though they're the same as in the included file, since the user wrote them.
+		let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(directory.join("config.json")).unwrap()).unwrap();
+		assert_eq!(saved["mods"], serde_json::json!({ "bar": "^2.0", "foo": "^1.0", "qux": "*" }));
+		assert_eq!(saved["holds"], serde_json::json!({ "foo": "1.2.0" }));
+	}
+
+	#[test]
+	fn test_load_with_install_directory_override() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+		let install_directory = directory.join("b");
+		let user_directory = directory.join("b-data");
+		std::fs::create_dir_all(install_directory.join("config")).unwrap();
+		std::fs::create_dir_all(&user_directory).unwrap();
+		std::fs::write(install_directory.join("config-path.cfg"), "config-path=__PATH__executable__/../../config\nuse-system-read-write-data-directories=false\n").unwrap();
+		std::fs::write(install_directory.join("config").join("config.ini"), format!("[path]\nwrite-data={}\n", user_directory.display())).unwrap();
+
+		assert_eq!(super::user_directory_of_install(&install_directory), Some(user_directory.clone()));
+
+		std::fs::write(directory.join("config.json"), r#"{ "version": "V2", "mods": {} }"#).unwrap();
+
+		let config = super::Config::load(Some(directory.join("config.json")), Some(&install_directory)).unwrap();
+		assert_ne!(config.user_directory.as_ref(), Some(&user_directory));
+		config.save().unwrap();
+
+		let saved = std::fs::read_to_string(directory.join("config.json")).unwrap();
+		assert!(!saved.contains("b-data"));
+	}
+
+	#[test]
+	fn test_parse_ini() {
+		let ini = super::parse_ini("\
+config-path=__PATH__executable__/../../config
+use-system-read-write-data-directories=false
+
+; version=3
+[path]
+read-data=__PATH__executable__/../../data
+write-data = __PATH__executable__/../..
+");
+
+		assert_eq!(ini[""]["config-path"], "__PATH__executable__/../../config");
+		assert_eq!(ini[""]["use-system-read-write-data-directories"], "false");
+		assert_eq!(ini["path"]["write-data"], "__PATH__executable__/../..");
+		assert!(!ini[""].contains_key("; version"));
+	}
+
+	#[test]
+	fn test_resolve_path() {
+		let install_directory = std::path::Path::new("/opt/factorio");
+
+		if !cfg!(target_os = "macos") {
+			assert_eq!(
+				super::resolve_path("__PATH__executable__/../..", install_directory).unwrap(),
+				std::path::Path::new("/opt/factorio"),
+			);
+			assert_eq!(
+				super::resolve_path("__PATH__executable__/../../config", install_directory).unwrap(),
+				std::path::Path::new("/opt/factorio/config"),
+			);
+		}
+
+		assert_eq!(super::resolve_path("/srv/factorio", install_directory).unwrap(), std::path::Path::new("/srv/factorio"));
+		assert_eq!(super::resolve_path("__PATH__unknown__/foo", install_directory), None);
+	}
 }
@@ -1,7 +1,7 @@
 #[derive(clap::Args)]
 pub(crate) struct EnableSubCommand {
-	#[arg(help = "mods to enable", required = true)]
-	names: Vec<factorio_mods_common::ModName>,
+	#[command(flatten)]
+	selection: Selection,
 }
 
 impl EnableSubCommand {
@@ -10,15 +10,15 @@ impl EnableSubCommand {
 		local_api: &factorio_mods_local::Api,
 		prompt_override: Option<bool>,
 	) -> anyhow::Result<()> {
-		enable_disable(self.names, local_api, prompt_override, true)?;
+		enable_disable(&self.selection, local_api, prompt_override, true)?;
 		Ok(())
 	}
 }
 
 #[derive(clap::Args)]
 pub(crate) struct DisableSubCommand {
-	#[arg(help = "mods to disable", required = true)]
-	names: Vec<factorio_mods_common::ModName>,
+	#[command(flatten)]
+	selection: Selection,
 }
 
 impl DisableSubCommand {
@@ -27,36 +27,71 @@ impl DisableSubCommand {
 		local_api: &factorio_mods_local::Api,
 		prompt_override: Option<bool>,
 	) -> anyhow::Result<()> {
-		enable_disable(self.names, local_api, prompt_override, false)?;
+		enable_disable(&self.selection, local_api, prompt_override, false)?;
 		Ok(())
 	}
 }
 
+/// The mods selected by the enable and disable subcommands.
+#[derive(clap::Args)]
+pub(crate) struct Selection {
+	#[arg(help = "mods to change, as names or glob patterns like 'bob*', optionally with a version like 'foo@1.2.3'", required_unless_present = "all")]
+	patterns: Vec<String>,
+
+	#[arg(help = "Change all installed mods", long = "all", conflicts_with = "patterns")]
+	all: bool,
+
+	#[arg(help = "mods to leave unchanged, as names or glob patterns", long = "except", num_args = 1..)]
+	except: Vec<String>,
+}
+
 pub(crate) fn enable_disable(
-	mods: Vec<factorio_mods_common::ModName>,
+	selection: &Selection,
 	local_api: &factorio_mods_local::Api,
 	prompt_override: Option<bool>,
 	enable: bool,
 ) -> anyhow::Result<()> {
 	use anyhow::Context;
 
+	let mut chosen_versions = Default::default();
+	let Some(selected) = find_matching_mods(local_api, &selection.patterns, selection.all, &mut chosen_versions)? else {
+		return Ok(());
+	};
+	let Some(except) = find_matching_mods(local_api, &selection.except, false, &mut Default::default())? else {
+		return Ok(());
+	};
+
 	let mut all_installed_mods: std::collections::BTreeMap<_, Vec<_>> = Default::default();
 	for mod_ in local_api.installed_mods().context("could not enumerate installed mods")? {
 		let mod_ = mod_.context("could not process an installed mod")?;
 		all_installed_mods.entry(mod_.info.name.clone()).or_default().push(mod_);
 	}
 
-	for (name, installed_mods) in &all_installed_mods {
-		if installed_mods.len() > 1 {
-			println!("There is more than one version of {name} installed. Run `fac update` or remove all but one version manually.");
-			return Ok(());
-		}
-	}
+	let active_versions = local_api.active_versions().context("could not parse installed mods status")?;
 
 	let mut graph = petgraph::Graph::new();
 
+	// If more than one version of a mod is installed, use the version that was chosen on the command line,
+	// else the version that is chosen in mod-list.json, else the newest version like the game does.
+	let mut has_multiple_versions = std::collections::BTreeSet::new();
 	let name_to_node_index: std::collections::BTreeMap<_, _> =
-		all_installed_mods.into_iter().map(|(name, mut installed_mods)| (name, graph.add_node(installed_mods.remove(0)))).collect();
+		all_installed_mods.into_iter().map(|(name, mut installed_mods)| {
+			if installed_mods.len() > 1 {
+				has_multiple_versions.insert(name.clone());
+			}
+
+			let chosen_version = chosen_versions.get(&name).or_else(|| active_versions.get(&name));
+			let index =
+				chosen_version
+				.and_then(|chosen_version| installed_mods.iter().position(|installed_mod| installed_mod.info.version == *chosen_version))
+				.unwrap_or_else(||
+					installed_mods.iter().enumerate()
+					.max_by(|(_, mod1), (_, mod2)| mod1.info.version.cmp(&mod2.info.version))
+					.map(|(index, _)| index)
+					.unwrap());
+
+			(name, graph.add_node(installed_mods.swap_remove(index)))
+		}).collect();
 
 	let mut edges_to_add = vec![];
 	for node_index in graph.node_indices() {
@@ -85,17 +120,50 @@ pub(crate) fn enable_disable(
 		graph.add_edge(edge_to_add.0, edge_to_add.1, ());
 	}
 
+	// A mod must not be changed if changing it would also change one of the excepted mods,
+	// ie if the excepted mod depends on it (when disabling) or it depends on the excepted mod (when enabling).
+	// These are the mods from which an excepted mod is reachable, so walk the graph backwards from the excepted mods to find them.
+	let mut protected = std::collections::BTreeSet::new();
+	{
+		let reversed_graph = petgraph::visit::Reversed(&graph);
+		for name in &except {
+			let node_index = name_to_node_index[name];
+			let bfs = petgraph::visit::Bfs::new(reversed_graph, node_index);
+			protected.extend(petgraph::visit::Walker::iter(bfs, reversed_graph));
+		}
+	}
+
 	let mut to_change = std::collections::BTreeSet::new();
+	let mut skipped = vec![];
 
-	for name in mods {
-		if let Some(&node_index) = name_to_node_index.get(&name) {
-			let bfs = petgraph::visit::Bfs::new(&graph, node_index);
-			to_change.extend(petgraph::visit::Walker::iter(bfs, &graph));
+	for name in selected {
+		let node_index = name_to_node_index[&name];
+		if protected.contains(&node_index) {
+			if !except.contains(&name) {
+				skipped.push(name);
+			}
+			continue;
 		}
-		else {
-			println!("No match found for mod {name}");
-			return Ok(());
+
+		let bfs = petgraph::visit::Bfs::new(&graph, node_index);
+		to_change.extend(petgraph::visit::Walker::iter(bfs, &graph));
+	}
+
+	if !skipped.is_empty() {
+		println!(
+			"The following mods will not be {} because {}:",
+			if enable { "enabled" } else { "disabled" },
+			if enable { "they depend on excepted mods" } else { "excepted mods depend on them" },
+		);
+		for name in skipped {
+			println!("{name}");
 		}
+		println!();
+	}
+
+	if to_change.is_empty() {
+		println!("Nothing to do.");
+		return Ok(());
 	}
 
 	let mut to_change: Vec<_> = to_change.into_iter().map(|node_index| &graph[node_index]).collect();
@@ -103,7 +171,12 @@ pub(crate) fn enable_disable(
 
 	println!("The following mods will be {}:", if enable { "enabled" } else { "disabled" });
 	for to_change in &to_change {
-		println!("{}", to_change.info.name);
+		if has_multiple_versions.contains(&to_change.info.name) {
+			println!("{} {}", to_change.info.name, to_change.info.version);
+		}
+		else {
+			println!("{}", to_change.info.name);
+		}
 	}
 
 	println!();
@@ -116,3 +189,52 @@ pub(crate) fn enable_disable(
 
 	Ok(())
 }
+
+/// Returns the names of the installed mods that match any of the given name patterns, or all installed mods if `all` is set.
+///
+/// Patterns of the form `pattern@version` only match that version of the mods, and the version is recorded in `chosen_versions`.
+///
+/// Returns `None` if any of the patterns doesn't match any installed mod.
+fn find_matching_mods(
+	local_api: &factorio_mods_local::Api,
+	patterns: &[String],
+	all: bool,
+	chosen_versions: &mut std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+) -> anyhow::Result<Option<std::collections::BTreeSet<factorio_mods_common::ModName>>> {
+	use anyhow::Context;
+
+	let mut result = std::collections::BTreeSet::new();
+
+	let patterns = if all { &["*".to_owned()][..] } else { patterns };
+
+	for pattern in patterns {
+		let (name_pattern, version) = match pattern.split_once('@') {
+			Some((name_pattern, version)) => {
+				let version: factorio_mods_common::ReleaseVersion =
+					version.parse()
+					.with_context(|| format!(r#"could not parse "{version}" as a valid version"#))?;
+				(name_pattern, Some(version))
+			},
+
+			None => (&**pattern, None),
+		};
+
+		let mut found = false;
+
+		for mod_ in local_api.find_installed_mods(Some(name_pattern), version.as_ref()).context("could not enumerate installed mods")? {
+			let mod_ = mod_.context("could not process an installed mod")?;
+			if let Some(version) = &version {
+				chosen_versions.insert(mod_.info.name.clone(), version.clone());
+			}
+			result.insert(mod_.info.name);
+			found = true;
+		}
+
+		if !found && !all {
+			println!("No match found for mod {pattern}");
+			return Ok(None);
+		}
+	}
+
+	Ok(Some(result))
+}
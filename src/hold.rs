@@ -0,0 +1,70 @@
+#[derive(clap::Args)]
+pub(crate) struct HoldSubCommand {
+	#[arg(help = "mods to hold at their installed versions", required = true)]
+	names: Vec<factorio_mods_common::ModName>,
+}
+
+impl HoldSubCommand {
+	pub(crate) fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		mut config: crate::config::Config,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let active_versions = local_api.active_versions().context("could not parse installed mods status")?;
+
+		let mut installed_versions: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+		for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+			let installed_mod = installed_mod.context("could not process an installed mod")?;
+			installed_versions.entry(installed_mod.info.name).or_default().push(installed_mod.info.version);
+		}
+
+		for name in self.names {
+			let Some(versions) = installed_versions.get(&name) else {
+				anyhow::bail!("mod {name} is not installed.");
+			};
+
+			// The game loads the version chosen in mod-list.json if it's installed, else the newest version.
+			let version =
+				active_versions.get(&name).filter(|version| versions.contains(version))
+				.or_else(|| versions.iter().max())
+				.unwrap()
+				.clone();
+
+			match config.holds.insert(name.clone(), version.clone()) {
+				Some(previous) if previous == version => println!("{name} is already held at {version}."),
+				Some(previous) => println!("Holding {name} at {version} instead of {previous}."),
+				None => println!("Holding {name} at {version}."),
+			}
+		}
+
+		config.save()?;
+
+		Ok(())
+	}
+}
+
+#[derive(clap::Args)]
+pub(crate) struct UnholdSubCommand {
+	#[arg(help = "mods to release", required = true)]
+	names: Vec<factorio_mods_common::ModName>,
+}
+
+impl UnholdSubCommand {
+	pub(crate) fn run(
+		self,
+		mut config: crate::config::Config,
+	) -> anyhow::Result<()> {
+		for name in self.names {
+			match config.holds.remove(&name) {
+				Some(version) => println!("Released {name} from {version}. It will be updated by the next `fac update`."),
+				None => println!("{name} is not held."),
+			}
+		}
+
+		config.save()?;
+
+		Ok(())
+	}
+}
@@ -1,13 +1,22 @@
 #[derive(clap::Args)]
 pub(crate) struct SubCommand {
-	#[arg(help = "requirements to install", required = true)]
+	#[arg(help = "requirements to install, like name or name@version, or paths of mod zip files", required = true)]
 	requirements: Vec<Requirement>,
+
+	#[arg(help = "Show the changelogs of mods that will be upgraded", long = "changelog")]
+	changelog: bool,
 }
 
 #[derive(Clone, Debug)]
-struct Requirement {
-	name: factorio_mods_common::ModName,
-	version: factorio_mods_common::ModVersionReq,
+enum Requirement {
+	/// A mod from the mods portal.
+	Portal {
+		name: factorio_mods_common::ModName,
+		version: factorio_mods_common::ModVersionReq,
+	},
+
+	/// A mod zip file, which is installed as a local mod.
+	File(std::path::PathBuf),
 }
 
 impl std::str::FromStr for Requirement {
@@ -19,6 +28,11 @@ impl std::str::FromStr for Requirement {
 		static REQUIREMENT_REGEX: std::sync::LazyLock<regex::Regex> =
 			std::sync::LazyLock::new(|| regex::Regex::new(r"^([^@]+)(?:@(.*))?").unwrap());
 
+		let path = std::path::Path::new(s);
+		if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip")) {
+			return Ok(Requirement::File(path.to_owned()));
+		}
+
 		let captures = REQUIREMENT_REGEX.captures(s).with_context(|| format!(r#"Could not parse requirement "{s}""#))?;
 		let name = factorio_mods_common::ModName(captures[1].to_owned());
 		let version_string = captures.get(2).map_or("*", |m| m.as_str());
@@ -27,7 +41,7 @@ impl std::str::FromStr for Requirement {
 			.with_context(|| format!(r#"could not parse "{version_string}" as a valid version requirement"#))?;
 		let version = factorio_mods_common::ModVersionReq(version);
 
-		Ok(Requirement {
+		Ok(Requirement::Portal {
 			name,
 			version,
 		})
@@ -42,13 +56,62 @@ impl SubCommand {
 		mut config: crate::config::Config,
 		prompt_override: Option<bool>,
 	) -> anyhow::Result<()> {
+		let (files, requirements): (Vec<_>, Vec<_>) =
+			self.requirements.into_iter()
+			.partition(|requirement| matches!(requirement, Requirement::File(_)));
+
+		// Mod zip files are only copied into the mods directory once the user confirms the diff.
+		let mut local_files = vec![];
+
+		for requirement in files {
+			let Requirement::File(path) = requirement else { unreachable!() };
+
+			let mod_ = parse_file(&path)?;
+			let name = &mod_.info.name;
+			let version = &mod_.info.version;
+
+			// The config entry marks the mod as local, so that it's never replaced by releases from the mods portal.
+			let entry = config.mods.as_mut().unwrap().entry(name.clone()).or_insert_with(|| crate::solve::exact_req(version).into());
+			entry.version = crate::solve::exact_req(version);
+			entry.source = Some(crate::solve::LOCAL_SOURCE.to_owned());
+			entry.git = None;
+
+			// A file that's already in the mods directory is found like any other installed mod.
+			let target = local_api.mods_directory().join(format!("{name}_{version}.zip"));
+			if std::fs::canonicalize(&path).ok() == std::fs::canonicalize(&target).ok() {
+				println!("{name} {version} is already installed at {}", target.display());
+			}
+			else {
+				local_files.push(mod_);
+			}
+		}
+
 		let mods = config.mods.as_mut().unwrap();
-		for requirement in self.requirements {
-			mods.insert(requirement.name, requirement.version);
+		for requirement in requirements {
+			let Requirement::Portal { name, version } = requirement else { unreachable!() };
+
+			match mods.entry(name) {
+				std::collections::btree_map::Entry::Occupied(mut entry) => entry.get_mut().version = version,
+				std::collections::btree_map::Entry::Vacant(entry) => { entry.insert(version.into()); },
+			}
 		}
 
-		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override).await?;
+		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override, crate::solve::DiffOptions { show_changelogs: self.changelog, local_files: &local_files, ..Default::default() }).await?;
 
 		Ok(())
 	}
 }
+
+/// Parses the mod zip file at the given path.
+fn parse_file(path: &std::path::Path) -> anyhow::Result<factorio_mods_local::InstalledMod> {
+	use anyhow::Context;
+
+	let path_displayable = path.display();
+
+	let mod_ = factorio_mods_local::InstalledMod::parse(path.to_owned()).with_context(|| format!("could not parse mod {path_displayable}"))?;
+	if mod_.mod_type != factorio_mods_local::InstalledModType::Zipped {
+		anyhow::bail!("{path_displayable} is not a mod zip file.");
+	}
+
+	Ok(mod_)
+}
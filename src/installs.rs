@@ -0,0 +1,346 @@
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[command(subcommand)]
+	subcommand: InstallsSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum InstallsSubCommand {
+	#[command(name = "list", about = "List the Factorio installs that were found, and which one is used")]
+	List,
+}
+
+impl SubCommand {
+	pub(crate) fn run(
+		self,
+		install_directory: Option<&std::path::Path>,
+	) {
+		match self.subcommand {
+			InstallsSubCommand::List => {
+				let installs = find();
+				if installs.is_empty() {
+					println!("No installs found.");
+				}
+				else {
+					println!("Found installs:");
+
+					for install in installs {
+						let selected = if Some(&*install.path) == install_directory { " (selected)" } else { "" };
+						println!("    {} {} {}{selected}", install.name, install.version, install.path.display());
+					}
+				}
+
+				if let Some(install_directory) = install_directory {
+					println!();
+					println!("Using install directory {}", install_directory.display());
+				}
+			},
+		}
+	}
+}
+
+/// A Factorio install that was found on this machine.
+#[derive(Debug)]
+pub(crate) struct Install {
+	/// The name of the install, which the config file can use to choose it.
+	///
+	/// The default Steam install and standalone installs are named after where they were found, like `steam` or `factorio`.
+	/// Installs in additional Steam libraries, and installs whose name is already taken, get a suffix derived from their path.
+	pub(crate) name: String,
+
+	/// The install directory, which contains `data/base/info.json`.
+	pub(crate) path: std::path::PathBuf,
+
+	/// The version of the game, from `data/base/info.json`.
+	pub(crate) version: factorio_mods_common::ReleaseVersion,
+}
+
+/// Finds all Factorio installs in the default and additional Steam libraries and in common standalone locations.
+///
+/// Steam installs come first, so the default Steam install is preferred when the config file doesn't choose one.
+pub(crate) fn find() -> Vec<Install> {
+	let mut candidates = vec![];
+
+	for (name, steam_directory) in steam_directories() {
+		for library in steam_libraries(&steam_directory) {
+			// Installs in additional libraries are named after the library, so that their names don't depend on which other libraries exist.
+			let is_additional_library = library != steam_directory;
+
+			let mut path = library;
+			path.push("steamapps");
+			path.push("common");
+			path.push("Factorio");
+			candidates.push((name.to_owned(), path, is_additional_library));
+		}
+	}
+
+	for parent in standalone_parent_directories() {
+		let Ok(entries) = std::fs::read_dir(&parent) else {
+			continue;
+		};
+
+		let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+		paths.sort();
+
+		for path in paths {
+			let Some(file_name) = path.file_name().and_then(std::ffi::OsStr::to_str) else {
+				continue;
+			};
+
+			let file_name = file_name.to_lowercase();
+			if !file_name.starts_with("factorio") {
+				continue;
+			}
+
+			let name = file_name.strip_suffix(".app").unwrap_or(&file_name).to_owned();
+			candidates.push((name, path, false));
+		}
+	}
+
+	let mut result: Vec<Install> = vec![];
+	let mut seen = std::collections::BTreeSet::new();
+
+	for (name, path, is_qualified) in candidates {
+		let Some((path, version)) = install_at(&path) else {
+			continue;
+		};
+
+		// ~/.steam/steam is usually a symlink to another Steam directory that is also searched.
+		let canonical_path = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+		if seen.contains(&canonical_path) {
+			continue;
+		}
+
+		// The config file stores this name, so it's derived from the path rather than from the order in which installs were found.
+		let name =
+			if is_qualified || result.iter().any(|install| install.name == name) {
+				qualified_name(&name, &canonical_path)
+			}
+			else {
+				name
+			};
+
+		seen.insert(canonical_path);
+		result.push(Install { name, path, version });
+	}
+
+	result
+}
+
+/// Returns the given name with a suffix that identifies the install at the given path.
+fn qualified_name(name: &str, path: &std::path::Path) -> String {
+	format!("{name}-{:08x}", crc32fast::hash(path.as_os_str().as_encoded_bytes()))
+}
+
+/// Returns the install directory at or inside the given path and the game version, if there is an install there.
+///
+/// On macOS the install directory is the `Contents` directory of the app bundle.
+fn install_at(path: &std::path::Path) -> Option<(std::path::PathBuf, factorio_mods_common::ReleaseVersion)> {
+	#[derive(serde::Deserialize)]
+	struct BaseInfo {
+		version: factorio_mods_common::ReleaseVersion,
+	}
+
+	[path.to_owned(), path.join("Contents"), path.join("factorio.app").join("Contents")]
+	.into_iter()
+	.find_map(|path| {
+		let base_info_file = std::fs::File::open(path.join("data").join("base").join("info.json")).ok()?;
+		let base_info: BaseInfo = serde_json::from_reader(std::io::BufReader::new(base_info_file)).ok()?;
+		Some((path, base_info.version))
+	})
+}
+
+/// The Steam directories to look for libraries in, along with the name to give to the installs in them.
+fn steam_directories() -> Vec<(&'static str, std::path::PathBuf)> {
+	let mut result = vec![];
+
+	if cfg!(windows) {
+		for var in ["ProgramFiles(x86)", "ProgramW6432", "ProgramFiles"] {
+			if let Some(path) = std::env::var_os(var) {
+				let mut path: std::path::PathBuf = path.into();
+				path.push("Steam");
+				result.push(("steam", path));
+			}
+		}
+	}
+	else {
+		if let Some(mut path) = dirs::home_dir() {
+			path.push(".steam");
+			path.push("steam");
+			result.push(("steam", path));
+		}
+
+		if let Some(mut path) = dirs::data_dir() {
+			path.push("Steam");
+			result.push(("steam", path));
+		}
+
+		if let Some(mut path) = dirs::home_dir() {
+			path.push(".var");
+			path.push("app");
+			path.push("com.valvesoftware.Steam");
+			path.push(".local");
+			path.push("share");
+			path.push("Steam");
+			result.push(("steam-flatpak", path));
+		}
+	}
+
+	result
+}
+
+/// Returns the libraries of the given Steam directory, including the Steam directory itself.
+///
+/// Additional libraries are listed in `steamapps/libraryfolders.vdf`.
+fn steam_libraries(steam_directory: &std::path::Path) -> Vec<std::path::PathBuf> {
+	let mut result = vec![steam_directory.to_owned()];
+
+	let library_folders_file_path = steam_directory.join("steamapps").join("libraryfolders.vdf");
+	if let Ok(library_folders) = std::fs::read_to_string(library_folders_file_path) {
+		for library in parse_library_folders(&library_folders) {
+			let library = std::path::PathBuf::from(library);
+			if !result.contains(&library) {
+				result.push(library);
+			}
+		}
+	}
+
+	result
+}
+
+/// Parses the library paths from the contents of Steam's `libraryfolders.vdf`.
+///
+/// Newer versions of the file have an object for each library with a `"path"` key. Older versions map the library index to its path directly.
+fn parse_library_folders(s: &str) -> Vec<String> {
+	enum Token {
+		String(String),
+		Open,
+		Close,
+	}
+
+	let mut tokens = vec![];
+
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'{' => tokens.push(Token::Open),
+			'}' => tokens.push(Token::Close),
+			'"' => {
+				let mut value = String::new();
+				while let Some(c) = chars.next() {
+					match c {
+						'"' => break,
+						'\\' => value.extend(chars.next()),
+						c => value.push(c),
+					}
+				}
+				tokens.push(Token::String(value));
+			},
+			_ => (),
+		}
+	}
+
+	let mut result = vec![];
+
+	let mut depth = 0_usize;
+	let mut tokens = tokens.into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match token {
+			Token::Open => depth += 1,
+			Token::Close => depth = depth.saturating_sub(1),
+			Token::String(key) => {
+				if let Some(Token::String(_)) = tokens.peek() {
+					let Some(Token::String(value)) = tokens.next() else { unreachable!() };
+
+					let is_path = match depth {
+						1 => key.bytes().all(|b| b.is_ascii_digit()),
+						2 => key == "path",
+						_ => false,
+					};
+					if is_path {
+						result.push(value);
+					}
+				}
+			},
+		}
+	}
+
+	result
+}
+
+/// The directories whose `factorio*` subdirectories are checked for standalone installs.
+fn standalone_parent_directories() -> Vec<std::path::PathBuf> {
+	let mut result = vec![];
+
+	if cfg!(windows) {
+		for var in ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"] {
+			if let Some(path) = std::env::var_os(var) {
+				result.push(path.into());
+			}
+		}
+	}
+	else if cfg!(target_os = "macos") {
+		result.push("/Applications".into());
+	}
+	else {
+		result.push("/opt".into());
+	}
+
+	if let Some(home_directory) = dirs::home_dir() {
+		result.push(home_directory.join("Games"));
+		result.push(home_directory);
+	}
+
+	result.dedup();
+
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_qualified_name() {
+		let name = super::qualified_name("steam", std::path::Path::new("/mnt/games/SteamLibrary/steamapps/common/Factorio"));
+		assert_eq!(name, super::qualified_name("steam", std::path::Path::new("/mnt/games/SteamLibrary/steamapps/common/Factorio")));
+		assert_ne!(name, super::qualified_name("steam", std::path::Path::new("/mnt/other/SteamLibrary/steamapps/common/Factorio")));
+		assert!(name.starts_with("steam-"));
+	}
+
+	#[test]
+	fn test_parse_library_folders() {
+		let library_folders = super::parse_library_folders(r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"436469950"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"label"		"Games"
+		"apps"
+		{
+			"427520"		"4040192000"
+		}
+	}
+}
+"#);
+		assert_eq!(library_folders, [r"C:\Program Files (x86)\Steam", r"D:\SteamLibrary"]);
+
+		let library_folders = super::parse_library_folders(r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1561832478"
+	"ContentStatsID"		"-158337411110787451"
+	"1"		"/mnt/games/SteamLibrary"
+}
+"#);
+		assert_eq!(library_folders, ["/mnt/games/SteamLibrary"]);
+	}
+}
@@ -0,0 +1,147 @@
+#[derive(clap::Args)]
+pub(crate) struct LinkSubCommand {
+	#[arg(help = "path of the directory of the mod's source, which contains its info.json", value_parser)]
+	path: std::path::PathBuf,
+
+	#[arg(help = "name the link <name>_<version> instead of <name>", long = "with-version")]
+	with_version: bool,
+}
+
+impl LinkSubCommand {
+	pub(crate) async fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		web_api: &factorio_mods_web::Api,
+		mut config: crate::config::Config,
+		prompt_override: Option<bool>,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let path_displayable = self.path.display();
+
+		let target = std::fs::canonicalize(&self.path).with_context(|| format!("could not canonicalize {path_displayable}"))?;
+
+		let mod_ = factorio_mods_local::InstalledMod::parse(target.clone()).with_context(|| format!("could not parse mod at {path_displayable}"))?;
+		if mod_.mod_type != factorio_mods_local::InstalledModType::Unpacked {
+			anyhow::bail!("{path_displayable} is not a directory. Use `fac install` to install a mod zip file.");
+		}
+
+		let name = mod_.info.name.clone();
+		let version = mod_.info.version.clone();
+
+		let link = local_api.mods_directory().join(if self.with_version { format!("{name}_{version}") } else { name.0.clone() });
+		let link_displayable = link.display();
+
+		// The link is only created once the user confirms the diff.
+		let mut local_links = vec![];
+
+		match std::fs::read_link(&link) {
+			Ok(existing_target) if existing_target == target => println!("{name} {version} is already linked at {link_displayable}"),
+
+			Ok(existing_target) => anyhow::bail!("{link_displayable} already links to {}. Run `fac unlink {name}` first.", existing_target.display()),
+
+			Err(_) if std::fs::symlink_metadata(&link).is_ok() => anyhow::bail!("{link_displayable} already exists."),
+
+			Err(_) => local_links.push((mod_.clone(), link.clone())),
+		}
+
+		let other_versions: Vec<_> =
+			local_api.installed_mods().context("could not enumerate installed mods")?
+			.filter_map(Result::ok)
+			.filter(|installed_mod| installed_mod.info.name == name && installed_mod.path != link)
+			.map(|installed_mod| installed_mod.info.version)
+			.collect();
+		if !other_versions.is_empty() {
+			println!(
+				"Other versions of {name} are also installed: {}. Make sure the game loads the linked one.",
+				itertools::join(&other_versions, ", "),
+			);
+		}
+
+		// The config entry marks the mod as local, so that it's never replaced by releases from the mods portal.
+		let mods = config.mods.get_or_insert_with(Default::default);
+		let entry = mods.entry(name).or_insert_with(|| factorio_mods_common::ModVersionReq(semver::VersionReq::STAR).into());
+		entry.source = Some(crate::solve::LOCAL_SOURCE.to_owned());
+		entry.git = None;
+
+		println!();
+
+		// Install the mod's dependencies.
+		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override, crate::solve::DiffOptions { local_links: &local_links, ..Default::default() }).await?;
+
+		Ok(())
+	}
+}
+
+#[derive(clap::Args)]
+pub(crate) struct UnlinkSubCommand {
+	#[arg(help = "mods to unlink", required = true)]
+	names: Vec<factorio_mods_common::ModName>,
+}
+
+impl UnlinkSubCommand {
+	pub(crate) fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		mut config: crate::config::Config,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let mut any_unlinked = false;
+
+		for name in self.names {
+			let mut unlinked = false;
+
+			for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+				let installed_mod = installed_mod.context("could not process an installed mod")?;
+				if installed_mod.info.name != name {
+					continue;
+				}
+
+				let path = installed_mod.path;
+				let is_link = std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_symlink());
+				if !is_link {
+					continue;
+				}
+
+				// Only the link is removed. The directory it links to is left alone.
+				remove_symlink_dir(&path).with_context(|| format!("could not remove link {}", path.display()))?;
+				println!("Unlinked {name} {} at {}", installed_mod.info.version, path.display());
+				unlinked = true;
+			}
+
+			if !unlinked {
+				println!("{name} is not linked.");
+				continue;
+			}
+
+			any_unlinked = true;
+
+			if let Some(mods) = &mut config.mods {
+				if mods.get(&name).is_some_and(|entry| entry.source.as_deref() == Some(crate::solve::LOCAL_SOURCE)) {
+					mods.remove(&name);
+				}
+			}
+		}
+
+		if any_unlinked {
+			config.save()?;
+
+			println!();
+			println!("Run `fac install` to install the unlinked mods from the mods portal instead.");
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(unix)]
+fn remove_symlink_dir(link: &std::path::Path) -> std::io::Result<()> {
+	std::fs::remove_file(link)
+}
+
+// Directory symlinks are removed like directories on Windows, but `remove_dir` doesn't touch the target.
+#[cfg(windows)]
+fn remove_symlink_dir(link: &std::path::Path) -> std::io::Result<()> {
+	std::fs::remove_dir(link)
+}
@@ -5,6 +5,7 @@ pub(crate) struct SubCommand {
 impl SubCommand {
 	pub(crate) fn run(
 		local_api: &factorio_mods_local::Api,
+		config: &crate::config::Config,
 	) -> anyhow::Result<()> {
 		use anyhow::Context;
 
@@ -39,6 +40,9 @@ impl SubCommand {
 				if matches!(installed_mod.0.mod_type, factorio_mods_local::InstalledModType::Unpacked) {
 					tags.push("unpacked");
 				}
+				if config.holds.contains_key(&installed_mod.0.info.name) {
+					tags.push("held");
+				}
 
 				let tags_string = if tags.is_empty() { String::new() } else { format!(" ({})", tags.join(", ")) };
 
@@ -46,6 +50,14 @@ impl SubCommand {
 			}
 		}
 
+		if !config.holds.is_empty() {
+			println!();
+			println!("Held mods:");
+			for (name, version) in &config.holds {
+				println!("    {name} {version}");
+			}
+		}
+
 		Ok(())
 	}
 }
@@ -3,15 +3,28 @@
 use anyhow::Context;
 
 mod enable_disable;
+mod hold;
 mod install;
+mod installs;
+mod link;
 mod list;
+mod modpack;
+mod outdated;
+mod repo;
 mod uninstall;
 mod search;
+mod serve;
+mod settings;
 mod show;
+mod sync_save;
 mod update;
 
+mod changelog;
 mod config;
+mod search_index;
 mod solve;
+#[cfg(test)]
+mod test_util;
 mod util;
 
 #[derive(clap::Parser)]
@@ -20,6 +33,37 @@ pub(crate) struct Options {
 	#[arg(help = "Path to fac config file. Defaults to .../fac/config.json", short = 'c', value_parser)]
 	config: Option<std::path::PathBuf>,
 
+	#[arg(
+		help = "Path of the Factorio installation. Overrides the config file and auto-detection",
+		long = "install-dir",
+		env = "FAC_INSTALL_DIR",
+		value_parser,
+	)]
+	install_directory: Option<std::path::PathBuf>,
+
+	#[arg(
+		help = "Path of the Factorio user data directory. Overrides the config file and auto-detection",
+		long = "user-dir",
+		env = "FAC_USER_DIR",
+		value_parser,
+	)]
+	user_directory: Option<std::path::PathBuf>,
+
+	#[arg(
+		help = "Path of the mods directory, like the game's --mod-directory. Defaults to the mods directory inside the user data directory",
+		long = "mods-dir",
+		env = "FAC_MODS_DIR",
+		value_parser,
+	)]
+	mods_directory: Option<std::path::PathBuf>,
+
+	#[arg(
+		help = "Base URL of a server to use instead of the mods portal, like one run by `fac serve`. Overrides the config file",
+		long = "portal-url",
+		env = "FAC_PORTAL_URL",
+	)]
+	portal_url: Option<String>,
+
 	#[arg(help = "Answer yes to all prompts", short = 'y')]
 	yes: bool,
 
@@ -32,27 +76,63 @@ pub(crate) struct Options {
 
 #[derive(clap::Subcommand)]
 pub(crate) enum SubCommand {
+	#[command(name = "config", about = "Manage the config file")]
+	Config(config::SubCommand),
+
 	#[command(name = "disable", about = "Disable mods")]
 	Disable(enable_disable::DisableSubCommand),
 
 	#[command(name = "enable", about = "Enable mods")]
 	Enable(enable_disable::EnableSubCommand),
 
+	#[command(name = "hold", about = "Hold mods at their installed versions, so that updates leave them alone")]
+	Hold(hold::HoldSubCommand),
+
 	#[command(name = "install", about = "Install (or update) mods", visible_alias = "add")]
 	Install(install::SubCommand),
 
+	#[command(name = "installs", about = "Show the Factorio installs that were found")]
+	Installs(installs::SubCommand),
+
+	#[command(name = "link", about = "Link a mod's source directory into the mods directory, for developing the mod")]
+	Link(link::LinkSubCommand),
+
 	#[command(name = "list", about = "List installed mods and their status")]
 	List(list::SubCommand),
 
+	#[command(name = "modpack", about = "Create modpacks from the config")]
+	Modpack(modpack::SubCommand),
+
+	#[command(name = "outdated", about = "List installed mods that have newer releases")]
+	Outdated(outdated::SubCommand),
+
+	#[command(name = "repo", about = "Manage mod repositories")]
+	Repo(repo::SubCommand),
+
 	#[command(name = "search", about = "Search the mods database")]
 	Search(search::SubCommand),
 
+	#[command(name = "serve", about = "Serve mods to other machines, with the same API as the mods portal")]
+	Serve(serve::SubCommand),
+
+	#[command(name = "settings", about = "Show and change mod settings")]
+	Settings(settings::SubCommand),
+
 	#[command(name = "show", about = "Show details about specific mods")]
 	Show(show::SubCommand),
 
+	#[command(name = "sync-save", about = "Install and enable exactly the mods that a save was made with")]
+	SyncSave(sync_save::SubCommand),
+
+	#[command(name = "unhold", about = "Release held mods, so that updates can change them again")]
+	Unhold(hold::UnholdSubCommand),
+
 	#[command(name = "uninstall", about = "Uninstall mods", visible_alias = "remove")]
 	Uninstall(uninstall::SubCommand),
 
+	#[command(name = "unlink", about = "Remove links made by `fac link`, leaving the linked directories alone")]
+	Unlink(link::UnlinkSubCommand),
+
 	#[command(name = "update", about = "Update installed mods")]
 	Update(update::SubCommand),
 }
@@ -70,20 +150,41 @@ async fn main() -> anyhow::Result<()> {
 		(true, true) => unreachable!(),
 	};
 
-	let mut config = crate::config::Config::load(options.config)?;
+	let mut config = crate::config::Config::load(options.config, options.install_directory.as_deref())?;
+
+	// Directories given on the command line or in the environment take precedence over the config file,
+	// which in turn takes precedence over auto-detection. clap already prefers the command line over the environment.
+	// They aren't stored in the config, so that they aren't saved to the config file either.
+	let install_directory = options.install_directory.as_ref().or(config.install_directory.as_ref());
+	let mods_directory = options.mods_directory.as_ref().or(config.mods_directory.as_ref());
 
-	let local_api: anyhow::Result<_> = match (&config.install_directory, &config.user_directory) {
+	// The user directory of an install given on the command line or in the environment takes precedence over the one in the config file,
+	// since that one belongs to the install in the config file.
+	let user_directory =
+		options.user_directory.clone()
+		.or_else(|| options.install_directory.as_deref().and_then(crate::config::user_directory_of_install))
+		.or_else(|| config.user_directory.clone());
+	let user_directory = user_directory.as_ref();
+
+	let local_api: anyhow::Result<_> = match (install_directory, user_directory) {
 		(Some(install_directory), Some(user_directory)) =>
-			factorio_mods_local::Api::new(install_directory, user_directory)
+			factorio_mods_local::Api::new(install_directory, user_directory, mods_directory.map(AsRef::as_ref))
 			.context("could not initialize local API"),
 
+		(None, _) if config.install.is_some() =>
+			Err(anyhow::Error::new(factorio_mods_local::Error::InstallDirectoryNotFound))
+			.with_context(|| format!(
+				"could not initialize local API. No install named {:?} was found. Run `fac installs list` to see the installs that were found.",
+				config.install.as_deref().unwrap(),
+			)),
+
 		(None, _) =>
 			Err(anyhow::Error::new(factorio_mods_local::Error::InstallDirectoryNotFound))
-			.context(r#"could not initialize local API. Consider setting "install_directory" to the path in the config file."#),
+			.context(r#"could not initialize local API. Consider passing --install-dir or setting "install_directory" to the path in the config file."#),
 
 		(_, None) =>
 			Err(anyhow::Error::new(factorio_mods_local::Error::UserDirectoryNotFound))
-			.context(r#"could not initialize local API. Consider setting "user_directory" to the path in the config file."#),
+			.context(r#"could not initialize local API. Consider passing --user-dir or setting "user_directory" to the path in the config file."#),
 	};
 
 	if config.mods.is_none() {
@@ -94,17 +195,26 @@ async fn main() -> anyhow::Result<()> {
 					local_api.installed_mods().context("could not enumerate installed mods")?
 					.map(|mod_|
 						mod_
-						.map(|mod_| (mod_.info.name, factorio_mods_common::ModVersionReq(semver::VersionReq::STAR)))
+						.map(|mod_| (mod_.info.name, factorio_mods_common::ModVersionReq(semver::VersionReq::STAR).into()))
 						.context("could not process an installed mod")))
 				.context("could not enumerate installed mods")?;
 			config.mods = Some(installed_mods);
 		}
 	}
 
-	let web_api = factorio_mods_web::Api::new().context("could not initialize web API");
+	let web_api = match options.portal_url.as_ref().or(config.portal_url.as_ref()) {
+		Some(portal_url) =>
+			portal_url.parse().with_context(|| format!("could not parse portal URL {portal_url}"))
+			.and_then(|portal_url| factorio_mods_web::Api::with_base_url(portal_url).context("could not initialize web API")),
+		None => factorio_mods_web::Api::new().context("could not initialize web API"),
+	};
 
 
 	match options.subcommand {
+		SubCommand::Config(parameters) => parameters.run(
+			config,
+		)?,
+
 		SubCommand::Disable(parameters) => parameters.run(
 			&local_api?,
 			prompt_override,
@@ -115,6 +225,11 @@ async fn main() -> anyhow::Result<()> {
 			prompt_override,
 		)?,
 
+		SubCommand::Hold(parameters) => parameters.run(
+			&local_api?,
+			config,
+		)?,
+
 		SubCommand::Install(parameters) => parameters.run(
 			&local_api?,
 			&web_api?,
@@ -122,18 +237,67 @@ async fn main() -> anyhow::Result<()> {
 			prompt_override,
 		).await?,
 
+		SubCommand::Installs(parameters) => parameters.run(
+			install_directory.map(AsRef::as_ref),
+		),
+
+		SubCommand::Link(parameters) => parameters.run(
+			&local_api?,
+			&web_api?,
+			config,
+			prompt_override,
+		).await?,
+
 		SubCommand::List(_) => list::SubCommand::run(
 			&local_api?,
+			&config,
 		)?,
 
+		SubCommand::Modpack(parameters) => parameters.run(
+			&local_api?,
+			web_api,
+			config,
+			prompt_override,
+		).await?,
+
+		SubCommand::Outdated(_) => outdated::SubCommand::run(
+			&local_api?,
+			&web_api?,
+			&config,
+		).await?,
+
+		SubCommand::Repo(parameters) => parameters.run()?,
+
 		SubCommand::Search(parameters) => parameters.run(
 			&web_api?,
 		).await?,
 
+		SubCommand::Serve(parameters) => parameters.run(
+			local_api,
+		).await?,
+
+		SubCommand::Settings(parameters) => parameters.run(
+			&local_api?,
+			prompt_override,
+		)?,
+
 		SubCommand::Show(parameters) => parameters.run(
+			local_api,
+			&web_api?,
+			prompt_override,
+		).await?,
+
+		SubCommand::SyncSave(parameters) => parameters.run(
+			&local_api?,
 			&web_api?,
+			config,
+			prompt_override,
 		).await?,
 
+		SubCommand::Unhold(parameters) => parameters.run(
+			config,
+		)?,
+
 		SubCommand::Uninstall(parameters) => parameters.run(
 			&local_api?,
 			&web_api?,
@@ -141,6 +305,11 @@ async fn main() -> anyhow::Result<()> {
 			prompt_override,
 		).await?,
 
+		SubCommand::Unlink(parameters) => parameters.run(
+			&local_api?,
+			config,
+		)?,
+
 		SubCommand::Update(parameters) => parameters.run(
 			&local_api?,
 			&web_api?,
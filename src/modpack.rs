@@ -0,0 +1,344 @@
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[command(subcommand)]
+	subcommand: ModpackSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum ModpackSubCommand {
+	#[command(name = "export", about = "Export the mods in the config as a modpack mod that depends on all of them")]
+	Export(ExportParameters),
+
+	#[command(name = "import", about = "Add the mods that a modpack mod depends on to the config, without installing the modpack itself")]
+	Import(ImportParameters),
+}
+
+#[derive(clap::Args)]
+struct ExportParameters {
+	#[arg(help = "name of the modpack mod", long = "name")]
+	name: factorio_mods_common::ModName,
+
+	#[arg(help = "version of the modpack mod", long = "version")]
+	version: factorio_mods_common::ReleaseVersion,
+
+	#[arg(help = "title of the modpack mod [default: the name]", long = "title")]
+	title: Option<factorio_mods_common::ModTitle>,
+
+	#[arg(help = "author of the modpack mod", long = "author", default_value = "")]
+	author: factorio_mods_common::AuthorName,
+
+	#[arg(help = "description of the modpack mod", long = "description")]
+	description: Option<factorio_mods_common::ModDescription>,
+
+	#[arg(help = "add a changelog.txt with this message as the changes of this version", long = "changelog")]
+	changelog: Option<String>,
+
+	#[arg(help = "path of the zip file to write [default: <name>_<version>.zip]", short = 'o', long = "output", value_parser)]
+	output: Option<std::path::PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct ImportParameters {
+	#[arg(help = "name of the modpack mod on the mods portal, or path of a modpack mod zip file or directory", value_parser)]
+	modpack: std::path::PathBuf,
+
+	#[arg(help = "also require the modpack's optional dependencies", long = "optional-dependencies")]
+	optional_dependencies: bool,
+
+	#[arg(help = "replace the mods in the config with the modpack's instead of adding them", long = "replace")]
+	replace: bool,
+}
+
+/// The contents of the modpack mod's `info.json`
+#[derive(serde::Serialize)]
+struct InfoJson<'a> {
+	name: &'a factorio_mods_common::ModName,
+	version: &'a factorio_mods_common::ReleaseVersion,
+	title: &'a str,
+	author: &'a str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description: Option<&'a str>,
+	factorio_version: String,
+	dependencies: Vec<factorio_mods_common::Dependency>,
+}
+
+impl SubCommand {
+	pub(crate) async fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		web_api: anyhow::Result<factorio_mods_web::Api>,
+		config: crate::config::Config,
+		prompt_override: Option<bool>,
+	) -> anyhow::Result<()> {
+		match self.subcommand {
+			ModpackSubCommand::Export(parameters) => parameters.run(local_api, &config),
+			ModpackSubCommand::Import(parameters) => parameters.run(local_api, &web_api?, config, prompt_override).await,
+		}
+	}
+}
+
+impl ExportParameters {
+	fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		config: &crate::config::Config,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let game_version = local_api.game_version();
+
+		let active_versions = local_api.active_versions().context("could not parse installed mods status")?;
+
+		let mut all_installed_versions: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+		for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+			let installed_mod = installed_mod.context("could not process an installed mod")?;
+			all_installed_versions.entry(installed_mod.info.name).or_default().push(installed_mod.info.version);
+		}
+
+		let mut dependencies = vec![factorio_mods_common::Dependency {
+			name: factorio_mods_common::ModName("base".to_owned()),
+			version: at_least(game_version),
+			kind: package::DependencyKind::Required,
+		}];
+
+		let mut not_installed = vec![];
+
+		for (name, entry) in config.mods.iter().flatten() {
+			// Mods that the config disables would be enabled by the game along with the modpack.
+			if entry.enabled == Some(false) || name == &dependencies[0].name {
+				continue;
+			}
+
+			// The game loads the version chosen in mod-list.json if it's installed, else the newest version.
+			let installed_version =
+				all_installed_versions.get(name)
+				.and_then(|versions|
+					active_versions.get(name).filter(|version| versions.contains(version))
+					.or_else(|| versions.iter().max()));
+
+			let version =
+				if let Some(installed_version) = installed_version {
+					at_least(installed_version)
+				}
+				else {
+					if !crate::util::BUILTIN_MODS.contains(&&*name.0) {
+						not_installed.push(name);
+					}
+					factorio_mods_common::ModVersionReq(semver::VersionReq::STAR)
+				};
+
+			dependencies.push(factorio_mods_common::Dependency {
+				name: name.clone(),
+				version,
+				kind: package::DependencyKind::Required,
+			});
+		}
+
+		if !not_installed.is_empty() {
+			println!(
+				"{} {} not installed, so the modpack depends on any version of {}.",
+				itertools::join(&not_installed, ", "),
+				if not_installed.len() == 1 { "is" } else { "are" },
+				if not_installed.len() == 1 { "it" } else { "them" },
+			);
+		}
+
+		let num_mods = dependencies.len() - 1;
+
+		let info_json = InfoJson {
+			name: &self.name,
+			version: &self.version,
+			title: self.title.as_ref().map_or(&*self.name.0, |title| &*title.0),
+			author: &self.author.0,
+			description: self.description.as_ref().map(|description| &*description.0),
+			factorio_version: format!("{}.{}", game_version.0.major, game_version.0.minor),
+			dependencies,
+		};
+		let info_json = serde_json::to_vec_pretty(&info_json).context("could not serialize info.json")?;
+
+		let changelog = self.changelog.map(|changelog| format!("\
+			---------------------------------------------------------------------------------------------------\n\
+			Version: {}\n  Changes:\n    - {changelog}\n",
+			self.version,
+		));
+
+		let output = self.output.unwrap_or_else(|| format!("{}_{}.zip", self.name, self.version).into());
+		let output_displayable = output.display();
+
+		// The game requires the files of a zipped mod to be in a directory named after the mod's name and version.
+		let toplevel = format!("{}_{}", self.name, self.version);
+
+		let result: anyhow::Result<()> = (|| {
+			let file = std::fs::File::create(&output)?;
+			let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+			let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+			zip.add_directory(&toplevel, options)?;
+
+			zip.start_file(format!("{toplevel}/info.json"), options)?;
+			std::io::Write::write_all(&mut zip, &info_json)?;
+
+			if let Some(changelog) = changelog {
+				zip.start_file(format!("{toplevel}/changelog.txt"), options)?;
+				std::io::Write::write_all(&mut zip, changelog.as_bytes())?;
+			}
+
+			let mut file = zip.finish()?;
+			std::io::Write::flush(&mut file)?;
+
+			Ok(())
+		})();
+		result.with_context(|| format!("could not write modpack to {output_displayable}"))?;
+
+		println!("Exported a modpack of {num_mods} mods to {output_displayable}");
+
+		Ok(())
+	}
+}
+
+impl ImportParameters {
+	async fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		web_api: &factorio_mods_web::Api,
+		mut config: crate::config::Config,
+		prompt_override: Option<bool>,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let modpack =
+			if self.modpack.exists() {
+				factorio_mods_local::InstalledMod::parse(self.modpack).context("could not parse modpack")?.info
+			}
+			else {
+				let name = factorio_mods_common::ModName(self.modpack.to_string_lossy().into_owned());
+				get_modpack_info(local_api, web_api, &name, prompt_override).await?
+			};
+
+		println!("Importing {} {} ...", modpack.name, modpack.version);
+
+		let mut required = vec![];
+		let mut optional = vec![];
+		let mut conflicts = vec![];
+		let mut builtin = vec![];
+
+		for dependency in modpack.dependencies {
+			// Mods that are part of the game can't be installed, only enabled in the game.
+			if crate::util::BUILTIN_MODS.contains(&&*dependency.name.0) {
+				if dependency.name.0 != "base" && dependency.kind == package::DependencyKind::Required {
+					builtin.push(dependency.name);
+				}
+				continue;
+			}
+
+			match dependency.kind {
+				package::DependencyKind::Required => required.push(dependency),
+				package::DependencyKind::Optional if self.optional_dependencies => required.push(dependency),
+				package::DependencyKind::Optional => optional.push(dependency),
+				package::DependencyKind::Conflicts => conflicts.push(dependency),
+			}
+		}
+
+		let mods = config.mods.get_or_insert_with(Default::default);
+		if self.replace {
+			mods.clear();
+		}
+
+		for dependency in required {
+			match mods.entry(dependency.name) {
+				std::collections::btree_map::Entry::Occupied(mut entry) => {
+					if entry.get().version != dependency.version {
+						println!("    {} {} -> {}", entry.key(), entry.get().version, dependency.version);
+						entry.get_mut().version = dependency.version;
+					}
+				},
+
+				std::collections::btree_map::Entry::Vacant(entry) => {
+					println!("    {} {}", entry.key(), dependency.version);
+					entry.insert(dependency.version.into());
+				},
+			}
+		}
+
+		if !optional.is_empty() {
+			println!();
+			println!(
+				"The modpack also has optional dependencies on {}. Pass --optional-dependencies to require them too.",
+				itertools::join(optional.iter().map(|dependency| &dependency.name), ", "),
+			);
+		}
+
+		if !builtin.is_empty() {
+			println!();
+			println!(
+				"The modpack also requires {}, which {} part of the game and must be enabled in the game.",
+				itertools::join(&builtin, ", "),
+				if builtin.len() == 1 { "is" } else { "are" },
+			);
+		}
+
+		if !conflicts.is_empty() {
+			println!();
+			println!(
+				"The modpack conflicts with {}. Make sure they're not installed.",
+				itertools::join(conflicts.iter().map(|dependency| &dependency.name), ", "),
+			);
+		}
+
+		config.save()?;
+
+		println!();
+		println!("Run `fac update` to install the mods of the modpack.");
+
+		Ok(())
+	}
+}
+
+/// Gets the `info.json` of the newest release of the given modpack mod on the mods portal that supports the installed game version.
+async fn get_modpack_info(
+	local_api: &factorio_mods_local::Api,
+	web_api: &factorio_mods_web::Api,
+	name: &factorio_mods_common::ModName,
+	prompt_override: Option<bool>,
+) -> anyhow::Result<factorio_mods_local::ModInfo> {
+	use anyhow::Context;
+
+	let game_version = local_api.game_version();
+
+	let modpack = match web_api.get(name).await {
+		Ok(modpack) => modpack,
+		Err(factorio_mods_web::Error::StatusCode(_, http::StatusCode::NOT_FOUND)) =>
+			anyhow::bail!("{name} is neither a file nor a mod on the mods portal."),
+		Err(err) => return Err(anyhow::Error::new(err).context(format!("could not retrieve mod {name}"))),
+	};
+
+	let release =
+		modpack.releases.into_iter()
+		.filter(|release| {
+			let game_version_req = factorio_mods_common::VersionReqMatcher {
+				version_req: &release.info_json.factorio_version.0,
+				is_base: true,
+			};
+			package::VersionReq::matches(&game_version_req, game_version)
+		})
+		.max_by(|release1, release2| release1.version.cmp(&release2.version))
+		.with_context(|| format!("{name} has no releases for game version {game_version}"))?;
+
+	let user_credentials = std::rc::Rc::new(crate::util::ensure_user_credentials(local_api, web_api, prompt_override).await?);
+
+	crate::solve::get_info_json(web_api, std::rc::Rc::new(release), user_credentials).await
+		.with_context(|| format!("could not get info.json of {name}"))
+}
+
+/// Returns a requirement for the given version or newer, which is the only kind of requirement that a modpack uses.
+fn at_least(version: &factorio_mods_common::ReleaseVersion) -> factorio_mods_common::ModVersionReq {
+	factorio_mods_common::ModVersionReq(semver::VersionReq {
+		comparators: vec![semver::Comparator {
+			op: semver::Op::GreaterEq,
+			major: version.0.major,
+			minor: Some(version.0.minor),
+			patch: Some(version.0.patch),
+			pre: semver::Prerelease::EMPTY,
+		}],
+	})
+}
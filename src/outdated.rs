@@ -0,0 +1,101 @@
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+}
+
+impl SubCommand {
+	pub(crate) async fn run(
+		local_api: &factorio_mods_local::Api,
+		web_api: &factorio_mods_web::Api,
+		config: &crate::config::Config,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let game_version = local_api.game_version();
+
+		let active_versions = local_api.active_versions().context("could not parse installed mods status")?;
+
+		let mut all_installed_versions: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+		for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+			let installed_mod = installed_mod.context("could not process an installed mod")?;
+			all_installed_versions.entry(installed_mod.info.name).or_default().push(installed_mod.info.version);
+		}
+
+		// The game loads the version chosen in mod-list.json if it's installed, else the newest version.
+		let installed_versions: std::collections::BTreeMap<_, _> =
+			all_installed_versions.into_iter()
+			.map(|(name, versions)| {
+				let version =
+					active_versions.get(&name).filter(|version| versions.contains(version)).cloned()
+					.or_else(|| versions.into_iter().max())
+					.unwrap();
+				(name, version)
+			})
+			.collect();
+
+		println!("Checking mods portal ...");
+
+		let mut portal_mods: futures_util::stream::FuturesOrdered<_> =
+			installed_versions.keys().map(|name| async move {
+				match web_api.get(name).await {
+					Ok(mod_) => Ok(Some(mod_)),
+					Err(factorio_mods_web::Error::StatusCode(_, http::StatusCode::NOT_FOUND)) => Ok(None),
+					Err(err) => Err(anyhow::Error::new(err).context(format!("could not retrieve mod {name}"))),
+				}
+			}).collect();
+
+		let mut outdated = vec![];
+		let mut held = vec![];
+
+		for (name, installed_version) in &installed_versions {
+			let portal_mod = futures_util::TryStreamExt::try_next(&mut portal_mods).await?.unwrap();
+
+			let newest_version =
+				portal_mod.iter()
+				.flat_map(|portal_mod| &portal_mod.releases)
+				.filter(|release| {
+					let game_version_req = factorio_mods_common::VersionReqMatcher {
+						version_req: &release.info_json.factorio_version.0,
+						is_base: true,
+					};
+					package::VersionReq::matches(&game_version_req, game_version)
+				})
+				.map(|release| &release.version)
+				.max()
+				.filter(|&newest_version| newest_version > installed_version)
+				.cloned();
+
+			if let Some(held_version) = config.holds.get(name) {
+				held.push((name, held_version, newest_version));
+			}
+			else if let Some(newest_version) = newest_version {
+				outdated.push((name, installed_version, newest_version));
+			}
+		}
+
+		println!("Checking mods portal ... done");
+		println!();
+
+		if outdated.is_empty() {
+			println!("All installed mods are up-to-date.");
+		}
+		else {
+			println!("Outdated mods:");
+			for (name, installed_version, newest_version) in outdated {
+				println!("    {name} {installed_version} -> {newest_version}");
+			}
+		}
+
+		if !held.is_empty() {
+			println!();
+			println!("Held mods:");
+			for (name, held_version, newest_version) in held {
+				match newest_version {
+					Some(newest_version) => println!("    {name} {held_version} (held, skipping {newest_version})"),
+					None => println!("    {name} {held_version} (held, up-to-date)"),
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
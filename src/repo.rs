@@ -0,0 +1,159 @@
+/// The name of the index file at the root of a mod repository.
+pub(crate) const INDEX_FILE_NAME: &str = "index.json";
+
+/// The index file of a mod repository, which lists the releases of each mod in the repository.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub(crate) struct Index {
+	pub(crate) mods: std::collections::BTreeMap<factorio_mods_common::ModName, Vec<IndexRelease>>,
+}
+
+/// A release of a mod in a mod repository's [`Index`]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub(crate) struct IndexRelease {
+	pub(crate) version: factorio_mods_common::ReleaseVersion,
+
+	pub(crate) factorio_version: factorio_mods_common::ModVersionReq,
+
+	#[serde(default)]
+	pub(crate) dependencies: Vec<factorio_mods_common::Dependency>,
+
+	/// The path of the release's zip file, relative to the root of the repository and separated by `/`
+	pub(crate) file: String,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[command(subcommand)]
+	subcommand: RepoSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum RepoSubCommand {
+	#[command(name = "index", about = "Write the index file of a directory of mod zip files, so that it can be used as a repository")]
+	Index(IndexParameters),
+}
+
+#[derive(clap::Args)]
+struct IndexParameters {
+	#[arg(help = "path of the directory of the repository", value_parser)]
+	directory: std::path::PathBuf,
+}
+
+impl SubCommand {
+	pub(crate) fn run(self) -> anyhow::Result<()> {
+		match self.subcommand {
+			RepoSubCommand::Index(parameters) => write_index(&parameters.directory),
+		}
+	}
+}
+
+/// Writes the index file of the mod zip files in the given directory and its subdirectories.
+pub(crate) fn write_index(directory: &std::path::Path) -> anyhow::Result<()> {
+	use anyhow::Context;
+
+	let mut files = vec![];
+	find_zip_files(directory, &mut files)?;
+	files.sort();
+
+	let mut index = Index::default();
+	let mut num_releases = 0_usize;
+
+	for path in files {
+		let path_displayable = path.display();
+
+		let mod_ = match factorio_mods_local::InstalledMod::parse(path.clone()) {
+			Ok(mod_) => mod_,
+			Err(err) => {
+				eprintln!("Skipping {path_displayable}: {:#}", anyhow::Error::new(err));
+				continue;
+			},
+		};
+
+		let file =
+			itertools::join(
+				path.strip_prefix(directory).unwrap()
+				.iter()
+				.map(|component| component.to_str().with_context(|| format!("{path_displayable} is not valid UTF-8"))).collect::<Result<Vec<_>, _>>()?,
+				"/");
+
+		let releases = index.mods.entry(mod_.info.name.clone()).or_default();
+		if let Some(existing) = releases.iter().find(|release| release.version == mod_.info.version) {
+			eprintln!("Skipping {path_displayable}: {} {} is already in the repository at {}", mod_.info.name, mod_.info.version, existing.file);
+			continue;
+		}
+
+		releases.push(IndexRelease {
+			version: mod_.info.version,
+			factorio_version: mod_.info.factorio_version,
+			dependencies: mod_.info.dependencies,
+			file,
+		});
+		num_releases += 1;
+	}
+
+	for releases in index.mods.values_mut() {
+		releases.sort_by(|release1, release2| release1.version.cmp(&release2.version));
+	}
+
+	let index_file_path = directory.join(INDEX_FILE_NAME);
+	let index_file_path_displayable = index_file_path.display();
+
+	let mut index_file =
+		std::fs::File::create(&index_file_path)
+		.with_context(|| format!("could not create index file {index_file_path_displayable}"))?;
+	serde_json::to_writer_pretty(&mut index_file, &index)
+	.with_context(|| format!("could not write to index file {index_file_path_displayable}"))?;
+
+	println!("Wrote {num_releases} releases of {} mods to {index_file_path_displayable}", index.mods.len());
+
+	Ok(())
+}
+
+/// Appends the paths of the `.zip` files in the given directory and its subdirectories to `files`.
+fn find_zip_files(directory: &std::path::Path, files: &mut Vec<std::path::PathBuf>) -> anyhow::Result<()> {
+	use anyhow::Context;
+
+	let directory_displayable = directory.display();
+
+	for entry in std::fs::read_dir(directory).with_context(|| format!("could not read directory {directory_displayable}"))? {
+		let entry = entry.with_context(|| format!("could not read directory {directory_displayable}"))?;
+		let path = entry.path();
+
+		let file_type = entry.file_type().with_context(|| format!("could not read {}", path.display()))?;
+		if file_type.is_dir() {
+			find_zip_files(&path, files)?;
+		}
+		else if path.extension() == Some("zip".as_ref()) {
+			files.push(path);
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_index() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+		crate::test_util::write_repository(directory);
+
+		let index: super::Index = serde_json::from_slice(&std::fs::read(directory.join(super::INDEX_FILE_NAME)).unwrap()).unwrap();
+
+		let releases: Vec<_> =
+			index.mods.iter()
+			.flat_map(|(name, releases)| releases.iter().map(move |release| (&*name.0, release.version.to_string(), &*release.file)))
+			.collect();
+		assert_eq!(releases, [
+			("bar", "2.0.0".to_owned(), "b/c/bar_2.0.0.zip"),
+			("foo", "1.0.0".to_owned(), "a/foo_1.0.0.zip"),
+			("foo", "1.1.0".to_owned(), "a/foo_1.1.0.zip"),
+		]);
+
+		let bar = &index.mods[&factorio_mods_common::ModName("bar".to_owned())][0];
+		assert_eq!(bar.dependencies.len(), 1);
+		assert_eq!(bar.dependencies[0].name.0, "foo");
+		assert_eq!(bar.factorio_version.to_string(), "^1.1");
+	}
+}
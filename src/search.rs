@@ -2,6 +2,9 @@
 pub(crate) struct SubCommand {
 	#[arg(help = "search string", default_value = "")]
 	query: String,
+
+	#[arg(help = "Download the whole mods list into the local search index, even if it's up-to-date", long = "refresh")]
+	refresh: bool,
 }
 
 impl SubCommand {
@@ -9,18 +12,16 @@ impl SubCommand {
 		self,
 		web_api: &factorio_mods_web::Api,
 	) -> anyhow::Result<()> {
-		use anyhow::Context;
-
 		let textwrap_options = crate::textwrap_options();
 
-		let mut mods = web_api.search(&self.query);
+		let index = crate::search_index::SearchIndex::load_or_refresh(web_api, self.refresh).await?;
 
-		while let Some(mod_) = futures_util::TryStreamExt::try_next(&mut mods).await.context("could not retrieve mods")? {
+		for mod_ in index.search(&self.query) {
 			println!("{}", mod_.title);
 			println!("    Name: {}", mod_.name);
 			println!();
 
-			for line in mod_.summary.0.lines() {
+			for line in mod_.summary.lines() {
 				for line in textwrap::wrap(line, textwrap_options.clone()) {
 					println!("{line}");
 				}
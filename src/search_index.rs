@@ -0,0 +1,571 @@
+use anyhow::Context;
+
+/// The index is refreshed from the web API if it's older than this.
+const MAX_AGE: std::time::Duration = std::time::Duration::from_hours(24);
+
+/// Refreshes only get the mods that were updated since the previous refresh, unless the whole list was last downloaded longer ago than this.
+///
+/// Incremental refreshes miss mods that were deleted, and don't update download counts, so the whole list is still downloaded occasionally.
+const FULL_REFRESH_MAX_AGE: std::time::Duration = std::time::Duration::from_hours(7 * 24);
+
+/// A local copy of the mods list of the web API, used to answer searches without downloading the whole list every time.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub(crate) struct SearchIndex {
+	/// Seconds since the UNIX epoch at which the index was last refreshed.
+	refreshed_at: u64,
+
+	/// Seconds since the UNIX epoch at which the whole mods list was last downloaded.
+	#[serde(default)]
+	fully_refreshed_at: u64,
+
+	/// The newest release time of any mod in the index, as returned by the web API. Mods with newer releases are fetched by the next incremental refresh.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	newest_release_at: Option<String>,
+
+	mods: Vec<IndexedMod>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub(crate) struct IndexedMod {
+	pub(crate) name: factorio_mods_common::ModName,
+	pub(crate) title: String,
+	pub(crate) owner: Vec<String>,
+	pub(crate) summary: String,
+
+	/// Only the per-mod endpoint of the web API returns the description, so this is only set for mods that were looked up individually.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub(crate) description: Option<String>,
+
+	pub(crate) downloads_count: u64,
+
+	/// The version of the latest release when this entry was last refreshed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	latest_version: Option<String>,
+
+	/// The time of the latest release when this entry was last refreshed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	latest_release_at: Option<String>,
+}
+
+impl SearchIndex {
+	/// Loads the index from disk, refreshing it from the web API first if it doesn't exist, is stale, or `force_refresh` is set.
+	///
+	/// If the refresh fails but an older copy of the index exists, the older copy is used.
+	pub(crate) async fn load_or_refresh(web_api: &factorio_mods_web::Api, force_refresh: bool) -> anyhow::Result<Self> {
+		let path = path(web_api)?;
+
+		let existing = Self::load(&path)?;
+
+		let is_fresh = existing.as_ref().is_some_and(|existing| now().saturating_sub(existing.refreshed_at) < MAX_AGE.as_secs());
+		if is_fresh && !force_refresh {
+			return Ok(existing.unwrap());
+		}
+
+		let mut index = existing.unwrap_or_default();
+
+		println!("Refreshing search index ...");
+
+		match index.refresh(web_api, force_refresh).await {
+			Ok(()) => {
+				println!("Refreshing search index ... done");
+				index.save(&path)?;
+			},
+
+			Err(err) if index.refreshed_at != 0 => {
+				eprintln!("Refreshing search index ... failed: {err:#}");
+				eprintln!("Using the existing search index instead.");
+			},
+
+			Err(err) => return Err(err),
+		}
+
+		println!();
+
+		Ok(index)
+	}
+
+	/// Loads the index of the given web API's server from disk if it exists, without refreshing it.
+	pub(crate) fn load_existing(web_api: &factorio_mods_web::Api) -> anyhow::Result<Option<Self>> {
+		let path = path(web_api)?;
+		Self::load(&path)
+	}
+
+	/// Saves the index of the given web API's server to disk.
+	pub(crate) fn save_default(&self, web_api: &factorio_mods_web::Api) -> anyhow::Result<()> {
+		let path = path(web_api)?;
+		self.save(&path)
+	}
+
+	/// Records the description of the given mod, if it's in the index.
+	pub(crate) fn set_description(&mut self, name: &factorio_mods_common::ModName, description: String) {
+		if let Some(mod_) = self.mods.iter_mut().find(|mod_| mod_.name == *name) {
+			mod_.description = Some(description);
+		}
+	}
+
+	/// Returns the mods that match the given query, most relevant first.
+	///
+	/// Every word of the query must match some field of the mod, either as a case-insensitive substring
+	/// or, for the name and title, as a word with a small edit distance.
+	pub(crate) fn search(&self, query: &str) -> Vec<&IndexedMod> {
+		let query = query.to_lowercase();
+		let terms: Vec<_> = query.split_whitespace().collect();
+
+		let mut results: Vec<_> =
+			self.mods.iter()
+			.filter_map(|mod_| mod_.score(&query, &terms).map(|score| (score, mod_)))
+			.collect();
+
+		results.sort_by(|(score1, mod1), (score2, mod2)|
+			score1.cmp(score2).reverse()
+			.then_with(|| mod1.downloads_count.cmp(&mod2.downloads_count).reverse())
+			.then_with(|| mod1.name.cmp(&mod2.name)));
+
+		results.into_iter().map(|(_, mod_)| mod_).collect()
+	}
+
+	/// Returns mods whose name or title is close to the given mod name, best match first.
+	///
+	/// This is used to suggest alternatives for mod names that don't exist, such as names with the wrong case,
+	/// names with typos, or titles that were used instead of names.
+	pub(crate) fn suggestions(&self, name: &factorio_mods_common::ModName) -> Vec<&IndexedMod> {
+		const MAX_SUGGESTIONS: usize = 5;
+
+		let normalized_name = normalize(&name.0);
+
+		let mut results: Vec<_> =
+			self.mods.iter()
+			.filter_map(|mod_| {
+				let normalized_mod_name = normalize(&mod_.name.0);
+				let normalized_mod_title = normalize(&mod_.title);
+
+				let rank =
+					if normalized_mod_name == normalized_name {
+						0
+					}
+					else if normalized_mod_title == normalized_name {
+						1
+					}
+					else if is_similar(&normalized_mod_name, &normalized_name) {
+						2 + edit_distance(&normalized_mod_name, &normalized_name)
+					}
+					else if is_similar(&normalized_mod_title, &normalized_name) {
+						3 + edit_distance(&normalized_mod_title, &normalized_name)
+					}
+					else {
+						return None;
+					};
+
+				Some((rank, mod_))
+			})
+			.collect();
+
+		results.sort_by(|(rank1, mod1), (rank2, mod2)|
+			rank1.cmp(rank2)
+			.then_with(|| mod1.downloads_count.cmp(&mod2.downloads_count).reverse())
+			.then_with(|| mod1.name.cmp(&mod2.name)));
+
+		results.into_iter().take(MAX_SUGGESTIONS).map(|(_, mod_)| mod_).collect()
+	}
+
+	/// Refreshes the index from the web API.
+	///
+	/// Only the mods that were updated since the previous refresh are downloaded, unless `force_full` is set or the index is due a full refresh.
+	async fn refresh(&mut self, web_api: &factorio_mods_web::Api, force_full: bool) -> anyhow::Result<()> {
+		let now = now();
+
+		let is_full =
+			force_full ||
+			self.newest_release_at.is_none() ||
+			now.saturating_sub(self.fully_refreshed_at) >= FULL_REFRESH_MAX_AGE.as_secs();
+
+		let mods =
+			if is_full {
+				let mut mods = vec![];
+
+				let mut stream = web_api.search("");
+				while let Some(mod_) = futures_util::TryStreamExt::try_next(&mut stream).await.context("could not retrieve mods")? {
+					mods.push(mod_);
+				}
+
+				mods
+			}
+			else {
+				let mut mods = vec![];
+
+				// Mods are listed most recently updated first, and a new release updates the mod,
+				// so the listing is read until a page where no mod has a release newer than the previous refresh.
+				let mut pages = web_api.recently_updated();
+				while let Some(page) = futures_util::TryStreamExt::try_next(&mut pages).await.context("could not retrieve mods")? {
+					let has_new_releases = page.iter().any(|mod_| is_newer(release_at(mod_), self.newest_release_at.as_deref()));
+					mods.extend(page);
+					if !has_new_releases {
+						break;
+					}
+				}
+
+				mods
+			};
+
+		// The index is only changed once all the mods were retrieved, so that it's intact if the refresh fails.
+		self.update(mods, is_full, now);
+
+		Ok(())
+	}
+
+	/// Updates the index with the given mods from the web API. If `is_full` is set, they're the whole mods list.
+	fn update(&mut self, mods: Vec<factorio_mods_web::SearchResponseMod>, is_full: bool, now: u64) {
+		let mut previous: std::collections::BTreeMap<_, _> =
+			std::mem::take(&mut self.mods).into_iter()
+			.map(|mod_| (mod_.name.clone(), mod_))
+			.collect();
+
+		let mut updated = std::collections::BTreeMap::new();
+		for mod_ in mods {
+			let previous_mod = previous.remove(&mod_.name);
+			let indexed_mod = IndexedMod::new(mod_, previous_mod);
+			updated.insert(indexed_mod.name.clone(), indexed_mod);
+		}
+
+		// An incremental refresh only gets the mods that were updated, so the others are kept.
+		// Mods that are missing from a full refresh were deleted.
+		if !is_full {
+			updated.append(&mut previous);
+		}
+
+		self.mods = updated.into_values().collect();
+		self.newest_release_at = self.mods.iter().filter_map(|mod_| mod_.latest_release_at.clone()).max();
+		self.refreshed_at = now;
+		if is_full {
+			self.fully_refreshed_at = now;
+		}
+	}
+
+	fn load(path: &std::path::Path) -> anyhow::Result<Option<Self>> {
+		match std::fs::File::open(path) {
+			Ok(file) => {
+				let file = std::io::BufReader::new(file);
+				match serde_json::from_reader(file) {
+					Ok(index) => Ok(Some(index)),
+
+					// The index is only a cache, so a corrupt index is simply rebuilt.
+					Err(err) => {
+						eprintln!("Ignoring corrupt search index {}: {err}", path.display());
+						Ok(None)
+					},
+				}
+			},
+
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+
+			Err(err) => Err(anyhow::Error::new(err).context(format!("could not read search index {}", path.display()))),
+		}
+	}
+
+	fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent).with_context(|| format!("could not create cache directory {}", parent.display()))?;
+		}
+
+		let file = std::fs::File::create(path).with_context(|| format!("could not create search index {}", path.display()))?;
+		let mut file = std::io::BufWriter::new(file);
+		serde_json::to_writer(&mut file, self).with_context(|| format!("could not write search index {}", path.display()))?;
+		std::io::Write::flush(&mut file).with_context(|| format!("could not write search index {}", path.display()))?;
+
+		Ok(())
+	}
+}
+
+impl IndexedMod {
+	/// Creates the index entry of the given mod from the web API.
+	///
+	/// Descriptions are not part of the mods list, so the description is carried over from the mod's previous entry unless the mod has had a new release since.
+	fn new(mod_: factorio_mods_web::SearchResponseMod, previous: Option<IndexedMod>) -> Self {
+		let latest_release_at = release_at(&mod_).map(ToOwned::to_owned);
+		let latest_version = mod_.latest_release.map(|release| release.version.to_string());
+
+		let description =
+			previous
+			.filter(|previous| previous.latest_version == latest_version)
+			.and_then(|previous| previous.description);
+
+		IndexedMod {
+			name: mod_.name,
+			title: mod_.title.0,
+			owner: mod_.owner.into_iter().map(|owner| owner.0).collect(),
+			summary: mod_.summary.0,
+			description,
+			downloads_count: mod_.downloads_count.0,
+			latest_version,
+			latest_release_at,
+		}
+	}
+
+	/// Computes the relevance of this mod for the given query, or `None` if it doesn't match.
+	fn score(&self, query: &str, terms: &[&str]) -> Option<u64> {
+		let name = self.name.0.to_lowercase();
+		let title = self.title.to_lowercase();
+
+		let mut score = 0;
+
+		if !query.is_empty() {
+			if name == query {
+				score += 100;
+			}
+			else if name.starts_with(query) {
+				score += 50;
+			}
+
+			if title == query {
+				score += 50;
+			}
+		}
+
+		for &term in terms {
+			let term_score =
+				if name.contains(term) {
+					10
+				}
+				else if title.contains(term) {
+					8
+				}
+				else if self.owner.iter().any(|owner| owner.to_lowercase().contains(term)) {
+					6
+				}
+				else if self.summary.to_lowercase().contains(term) {
+					3
+				}
+				else if self.description.as_ref().is_some_and(|description| description.to_lowercase().contains(term)) {
+					1
+				}
+				else if words(&name).chain(words(&title)).any(|word| is_similar(word, term)) {
+					2
+				}
+				else {
+					return None;
+				};
+
+			score += term_score;
+		}
+
+		Some(score)
+	}
+}
+
+/// Returns the time of the given mod's latest release, if it has any.
+fn release_at(mod_: &factorio_mods_web::SearchResponseMod) -> Option<&str> {
+	mod_.latest_release.as_ref().map(|release| &*release.released_at.0)
+}
+
+/// Returns true if `released_at` is newer than `newest_release_at`.
+///
+/// The web API returns times in a fixed-width ISO 8601 format, so they can be compared as strings.
+fn is_newer(released_at: Option<&str>, newest_release_at: Option<&str>) -> bool {
+	released_at > newest_release_at
+}
+
+/// Returns true if the two words are within a small edit distance of each other, scaled to the length of the second word.
+pub(crate) fn is_similar(word: &str, term: &str) -> bool {
+	let term_len = term.chars().count();
+	let max_distance = match term_len {
+		0..=3 => return false,
+		4..=7 => 1,
+		_ => 2,
+	};
+
+	let word_len = word.chars().count();
+	if word_len.abs_diff(term_len) > max_distance {
+		return false;
+	}
+
+	edit_distance(word, term) <= max_distance
+}
+
+/// Computes the Levenshtein distance between the two strings.
+pub(crate) fn edit_distance(s1: &str, s2: &str) -> usize {
+	let s2: Vec<_> = s2.chars().collect();
+
+	let mut previous_row: Vec<_> = (0..=s2.len()).collect();
+	let mut current_row = vec![0; s2.len() + 1];
+
+	for (i, c1) in s1.chars().enumerate() {
+		current_row[0] = i + 1;
+
+		for (j, &c2) in s2.iter().enumerate() {
+			let substitution_cost = usize::from(c1 != c2);
+			current_row[j + 1] =
+				(previous_row[j] + substitution_cost)
+				.min(previous_row[j + 1] + 1)
+				.min(current_row[j] + 1);
+		}
+
+		std::mem::swap(&mut previous_row, &mut current_row);
+	}
+
+	previous_row[s2.len()]
+}
+
+/// Splits a lowercased name or title into words.
+fn words(s: &str) -> impl Iterator<Item = &str> {
+	s.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty())
+}
+
+/// Lowercases the given name or title and strips everything but letters and digits from it,
+/// so that names like `squeak-through` and `Squeak Through` compare equal.
+fn normalize(s: &str) -> String {
+	s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Returns the path of the index of the given web API's server.
+///
+/// Each server has its own index, named after a hash of its base URL, so that the mods of a server like `fac serve` aren't mixed into the index of the official mods portal.
+fn path(web_api: &factorio_mods_web::Api) -> anyhow::Result<std::path::PathBuf> {
+	let mut path = dirs::cache_dir().context("could not derive path to cache directory")?;
+	path.push("fac");
+	path.push(file_name(web_api));
+	Ok(path)
+}
+
+fn file_name(web_api: &factorio_mods_web::Api) -> String {
+	if web_api.is_official() {
+		"search-index.json".to_owned()
+	}
+	else {
+		format!("search-index-{:08x}.json", crc32fast::hash(web_api.base_url().as_str().as_bytes()))
+	}
+}
+
+fn now() -> u64 {
+	std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+	fn mod_(name: &str, title: &str, summary: &str, downloads_count: u64, latest_release: Option<(&str, &str)>) -> factorio_mods_web::SearchResponseMod {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"title": title,
+			"owner": "someone",
+			"summary": summary,
+			"downloads_count": downloads_count,
+			"latest_release": latest_release.map(|(version, released_at)| serde_json::json!({
+				"version": version,
+				"info_json": { "factorio_version": "1.1" },
+				"download_url": format!("/download/{name}/1"),
+				"file_name": format!("{name}_{version}.zip"),
+				"released_at": released_at,
+				"sha1": "",
+			})),
+		})).unwrap()
+	}
+
+	fn names<'a>(mods: impl IntoIterator<Item = &'a super::IndexedMod>) -> Vec<&'a str> {
+		mods.into_iter().map(|mod_| &*mod_.name.0).collect()
+	}
+
+	fn index() -> super::SearchIndex {
+		let mut index = super::SearchIndex::default();
+		index.update(vec![
+			mod_("AutoDeconstruct", "Auto Deconstruct", "Marks drills that have no more resources to mine for deconstruction.", 100, Some(("1.0.0", "2024-01-01T00:00:00.000000Z"))),
+			mod_("Squeak Through", "Squeak Through", "Walk between entities.", 1000, Some(("1.8.2", "2024-02-01T00:00:00.000000Z"))),
+			mod_("squeak-through-2", "Squeak Through 2", "Walk between entities, again.", 10, Some(("2.0.0", "2024-03-01T00:00:00.000000Z"))),
+			mod_("deconstruction-planner", "Deconstruction Planner", "Plans deconstruction.", 50, None),
+		], true, 1);
+		index
+	}
+
+	#[test]
+	fn test_edit_distance() {
+		assert_eq!(super::edit_distance("", ""), 0);
+		assert_eq!(super::edit_distance("abc", ""), 3);
+		assert_eq!(super::edit_distance("", "abc"), 3);
+		assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+		assert_eq!(super::edit_distance("flaw", "lawn"), 2);
+		assert_eq!(super::edit_distance("héllo", "hello"), 1);
+	}
+
+	#[test]
+	fn test_is_similar() {
+		// Short terms must match exactly.
+		assert!(!super::is_similar("bob", "bab"));
+
+		assert!(super::is_similar("squeak", "sqeak"));
+		assert!(!super::is_similar("squeak", "sqek"));
+
+		assert!(super::is_similar("deconstruct", "deconstrcut"));
+		assert!(!super::is_similar("deconstruct", "instruct"));
+	}
+
+	#[test]
+	fn test_search() {
+		let index = index();
+
+		// Exact name matches rank first, then other matches by score and downloads.
+		assert_eq!(names(index.search("squeak")), ["Squeak Through", "squeak-through-2"]);
+		assert_eq!(names(index.search("deconstruction-planner")), ["deconstruction-planner"]);
+		// Names that start with the query rank before names that only contain it.
+		assert_eq!(names(index.search("deconstruct")), ["deconstruction-planner", "AutoDeconstruct"]);
+
+		// Every term must match, in any field.
+		assert_eq!(names(index.search("walk again")), ["squeak-through-2"]);
+		assert_eq!(names(index.search("walk drills")), Vec::<&str>::new());
+
+		// Small typos in names and titles are tolerated.
+		assert_eq!(names(index.search("sqeak")), ["Squeak Through", "squeak-through-2"]);
+	}
+
+	#[test]
+	fn test_suggestions() {
+		let index = index();
+
+		let suggestions = |name: &str| names(index.suggestions(&factorio_mods_common::ModName(name.to_owned())));
+		assert_eq!(suggestions("autodeconstruct"), ["AutoDeconstruct"]);
+		assert_eq!(suggestions("squeak-through"), ["Squeak Through", "squeak-through-2"]);
+		assert_eq!(suggestions("Deconstruction Planer"), ["deconstruction-planner"]);
+		assert_eq!(suggestions("nothing-like-it"), Vec::<&str>::new());
+	}
+
+	#[test]
+	fn test_update() {
+		let mut index = index();
+		assert_eq!(index.newest_release_at.as_deref(), Some("2024-03-01T00:00:00.000000Z"));
+		index.set_description(&factorio_mods_common::ModName("AutoDeconstruct".to_owned()), "Description".to_owned());
+		index.set_description(&factorio_mods_common::ModName("Squeak Through".to_owned()), "Description".to_owned());
+
+		// An incremental update only changes the updated mods. Descriptions are kept unless there's a new release.
+		index.update(vec![
+			mod_("Squeak Through", "Squeak Through", "Walk between entities.", 2000, Some(("1.8.3", "2024-04-01T00:00:00.000000Z"))),
+			mod_("new-mod", "New Mod", "New.", 0, Some(("0.1.0", "2024-04-02T00:00:00.000000Z"))),
+		], false, 2);
+		assert_eq!(names(&index.mods), ["AutoDeconstruct", "Squeak Through", "deconstruction-planner", "new-mod", "squeak-through-2"]);
+		assert_eq!(index.mods[0].description.as_deref(), Some("Description"));
+		assert_eq!(index.mods[1].description, None);
+		assert_eq!(index.mods[1].downloads_count, 2000);
+		assert_eq!(index.newest_release_at.as_deref(), Some("2024-04-02T00:00:00.000000Z"));
+		assert_eq!((index.refreshed_at, index.fully_refreshed_at), (2, 1));
+
+		// A full update removes mods that were deleted.
+		index.update(vec![
+			mod_("AutoDeconstruct", "Auto Deconstruct", "Marks drills.", 100, Some(("1.0.0", "2024-01-01T00:00:00.000000Z"))),
+		], true, 3);
+		assert_eq!(names(&index.mods), ["AutoDeconstruct"]);
+		assert_eq!(index.mods[0].description.as_deref(), Some("Description"));
+		assert_eq!((index.refreshed_at, index.fully_refreshed_at), (3, 3));
+
+		assert!(super::is_newer(Some("2024-01-01T00:00:00.000001Z"), Some("2024-01-01T00:00:00.000000Z")));
+		assert!(super::is_newer(Some("2024-01-01T00:00:00.000000Z"), None));
+		assert!(!super::is_newer(None, Some("2024-01-01T00:00:00.000000Z")));
+	}
+
+	#[test]
+	fn test_file_name() {
+		let web_api = |base_url: &str| factorio_mods_web::Api::with_base_url(base_url.parse().unwrap()).unwrap();
+
+		assert_eq!(super::file_name(&factorio_mods_web::Api::new().unwrap()), "search-index.json");
+
+		let lan = super::file_name(&web_api("http://192.168.1.2:8080/"));
+		assert_ne!(lan, "search-index.json");
+		assert_eq!(lan, super::file_name(&web_api("http://192.168.1.2:8080")));
+		assert_ne!(lan, super::file_name(&web_api("http://192.168.1.3:8080/")));
+	}
+}
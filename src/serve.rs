@@ -0,0 +1,531 @@
+#![allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_sign_loss,
+)]
+
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[arg(help = "path of a repository directory made with `fac repo index` [default: serve the zipped mods in the mods directory]", value_parser)]
+	directory: Option<std::path::PathBuf>,
+
+	#[arg(
+		help = "address and port to listen on. Use 0.0.0.0:8080 to serve other machines, which exposes the served mods on every network interface.",
+		long = "listen",
+		default_value = "127.0.0.1:8080",
+	)]
+	listen: std::net::SocketAddr,
+}
+
+impl SubCommand {
+	pub(crate) async fn run(
+		self,
+		local_api: anyhow::Result<factorio_mods_local::Api>,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let paths = match &self.directory {
+			Some(directory) => {
+				let index_file_path = directory.join(crate::repo::INDEX_FILE_NAME);
+				let index_file_path_displayable = index_file_path.display();
+
+				let index = std::fs::read(&index_file_path).with_context(|| format!("could not read index file {index_file_path_displayable}"))?;
+				let index: crate::repo::Index = serde_json::from_slice(&index).with_context(|| format!("could not parse index file {index_file_path_displayable}"))?;
+
+				index.mods.into_values().flatten().map(|release| directory.join(release.file)).collect()
+			},
+
+			None =>
+				local_api?.installed_mods().context("could not enumerate installed mods")?
+				.filter_map(|installed_mod| match installed_mod {
+					Ok(installed_mod) if installed_mod.mod_type == factorio_mods_local::InstalledModType::Zipped => Some(Ok(installed_mod.path)),
+					Ok(_) => None,
+					Err(err) => Some(Err(anyhow::Error::new(err).context("could not process an installed mod"))),
+				})
+				.collect::<anyhow::Result<Vec<_>>>()?,
+		};
+
+		let catalog = Catalog::new(paths);
+
+		println!(
+			"Serving {} releases of {} mods on http://{}/",
+			catalog.mods.values().map(Vec::len).sum::<usize>(), catalog.mods.len(), self.listen,
+		);
+		if self.listen.ip().is_loopback() {
+			println!("Only this machine can use it. Pass `--listen 0.0.0.0:{}` to serve other machines too.", self.listen.port());
+		}
+		else {
+			println!("Other machines can use it with `fac --portal-url http://<address of this machine>:{}/ ...`", self.listen.port());
+		}
+		println!();
+
+		let catalog = std::sync::Arc::new(catalog);
+
+		let make_service = hyper::service::make_service_fn(move |_| {
+			let catalog = catalog.clone();
+			async move {
+				Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |request| {
+					let response = handle(&catalog, &request);
+					println!("{} {} {}", request.method(), request.uri(), response.status());
+					futures_util::future::ok::<_, std::convert::Infallible>(response)
+				}))
+			}
+		});
+
+		hyper::Server::try_bind(&self.listen).with_context(|| format!("could not listen on {}", self.listen))?
+			.serve(make_service).await
+			.context("could not serve")?;
+
+		Ok(())
+	}
+}
+
+/// The mod releases that are served, by mod name. The releases of each mod are sorted by version.
+struct Catalog {
+	mods: std::collections::BTreeMap<factorio_mods_common::ModName, Vec<Release>>,
+}
+
+struct Release {
+	info: factorio_mods_local::ModInfo,
+	path: std::path::PathBuf,
+	len: u64,
+	released_at: factorio_mods_web::DateTime,
+}
+
+impl Catalog {
+	/// Parses the mod zip files at the given paths. Files that can't be parsed are skipped.
+	fn new(paths: Vec<std::path::PathBuf>) -> Self {
+		let mut mods: std::collections::BTreeMap<_, Vec<Release>> = Default::default();
+
+		for path in paths {
+			let path_displayable = path.display();
+
+			let mod_ = match factorio_mods_local::InstalledMod::parse(path.clone()) {
+				Ok(mod_) => mod_,
+				Err(err) => {
+					eprintln!("Skipping {path_displayable}: {:#}", anyhow::Error::new(err));
+					continue;
+				},
+			};
+
+			let metadata = match std::fs::metadata(&path) {
+				Ok(metadata) => metadata,
+				Err(err) => {
+					eprintln!("Skipping {path_displayable}: {err}");
+					continue;
+				},
+			};
+
+			let releases = mods.entry(mod_.info.name.clone()).or_default();
+			if releases.iter().any(|release| release.info.version == mod_.info.version) {
+				eprintln!("Skipping {path_displayable}: {} {} is already served", mod_.info.name, mod_.info.version);
+				continue;
+			}
+
+			releases.push(Release {
+				info: mod_.info,
+				path,
+				len: metadata.len(),
+				released_at: date_time(metadata.modified().unwrap_or(std::time::UNIX_EPOCH)),
+			});
+		}
+
+		for releases in mods.values_mut() {
+			releases.sort_by(|release1, release2| release1.info.version.cmp(&release2.info.version));
+		}
+
+		Catalog { mods }
+	}
+}
+
+/// The response of `/api/mods`. All mods are returned in a single page.
+#[derive(serde::Serialize)]
+struct PagedResponse {
+	pagination: Option<()>,
+	results: Vec<factorio_mods_web::SearchResponseMod>,
+}
+
+/// Characters that are percent-encoded in the mod names in download URLs.
+const PATH_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+fn handle(catalog: &Catalog, request: &http::Request<hyper::Body>) -> http::Response<hyper::Body> {
+	let is_head = match *request.method() {
+		http::Method::GET => false,
+		http::Method::HEAD => true,
+		_ => return status(http::StatusCode::METHOD_NOT_ALLOWED),
+	};
+
+	let segments: Option<Vec<_>> =
+		request.uri().path().trim_start_matches('/').split('/')
+		.map(|segment| percent_encoding::percent_decode_str(segment).decode_utf8().ok())
+		.collect();
+	let Some(segments) = segments else {
+		return status(http::StatusCode::BAD_REQUEST);
+	};
+
+	match &segments[..] {
+		[api, mods] if api == "api" && mods == "mods" => {
+			let mut results: Vec<_> =
+				catalog.mods.iter()
+				.map(|(name, releases)| {
+					let newest = releases.last().expect("mods in the catalog have at least one release");
+					factorio_mods_web::SearchResponseMod {
+						name: name.clone(),
+						title: newest.info.title.clone(),
+						owner: newest.info.author.clone(),
+						summary: summary(&newest.info),
+						latest_release: Some(mod_release(newest)),
+						downloads_count: factorio_mods_web::DownloadCount(0),
+					}
+				})
+				.collect();
+
+			// Mods are sorted by name, unless the client asks for the most recently updated mods first, like `fac search` does to refresh its index.
+			// The newest release of each mod is the last time the mod was updated.
+			let query: Vec<_> = request.uri().query().unwrap_or_default().split('&').collect();
+			if query.contains(&"sort=updated_at") && query.contains(&"sort_order=desc") {
+				results.sort_by(|mod1, mod2| {
+					let released_at = |mod_: &factorio_mods_web::SearchResponseMod| mod_.latest_release.as_ref().map(|release| release.released_at.0.clone());
+					released_at(mod2).cmp(&released_at(mod1))
+				});
+			}
+
+			json(&PagedResponse { pagination: None, results }, is_head)
+		},
+
+		[api, mods, name] if api == "api" && mods == "mods" => mod_(catalog, name, is_head),
+
+		[api, mods, name, full] if api == "api" && mods == "mods" && full == "full" => mod_(catalog, name, is_head),
+
+		[download, name, version] if download == "download" => {
+			let release =
+				catalog.mods.get(&factorio_mods_common::ModName(name.clone().into_owned()))
+				.and_then(|releases| releases.iter().find(|release| release.info.version.to_string() == *version));
+			let Some(release) = release else {
+				return status(http::StatusCode::NOT_FOUND);
+			};
+
+			let range = request.headers().get(http::header::RANGE).and_then(|range| range.to_str().ok());
+			file(release, range, is_head)
+		},
+
+		_ => status(http::StatusCode::NOT_FOUND),
+	}
+}
+
+fn mod_(catalog: &Catalog, name: &str, is_head: bool) -> http::Response<hyper::Body> {
+	let name = factorio_mods_common::ModName(name.to_owned());
+	let Some(releases) = catalog.mods.get(&name) else {
+		return status(http::StatusCode::NOT_FOUND);
+	};
+
+	let oldest = releases.first().expect("mods in the catalog have at least one release");
+	let newest = releases.last().expect("mods in the catalog have at least one release");
+
+	let mod_ = factorio_mods_web::Mod {
+		name,
+		title: newest.info.title.clone(),
+		owner: newest.info.author.clone(),
+		summary: summary(&newest.info),
+		releases: releases.iter().map(mod_release).collect(),
+		downloads_count: factorio_mods_web::DownloadCount(0),
+		description: newest.info.description.clone(),
+		category: None,
+		tags: vec![],
+		license: None,
+		homepage: newest.info.homepage.clone(),
+		source_url: None,
+		created_at: Some(oldest.released_at.clone()),
+		updated_at: Some(newest.released_at.clone()),
+		deprecated: false,
+	};
+	json(&mod_, is_head)
+}
+
+fn mod_release(release: &Release) -> factorio_mods_web::ModRelease {
+	let name = &release.info.name;
+	let version = &release.info.version;
+
+	factorio_mods_web::ModRelease {
+		version: version.clone(),
+		info_json: factorio_mods_web::ModReleaseInfo {
+			factorio_version: release.info.factorio_version.clone(),
+			dependencies: release.info.dependencies.clone(),
+		},
+		// Relative to the base URL, so that the server can also be used behind a reverse proxy that serves it under a subpath.
+		download_url: factorio_mods_common::Url(format!("download/{}/{version}", percent_encoding::utf8_percent_encode(&name.0, PATH_SEGMENT))),
+		filename: factorio_mods_web::Filename(format!("{name}_{version}.zip")),
+		released_at: release.released_at.clone(),
+		// `fac` doesn't check the hash, and computing it would mean reading every file.
+		sha1: factorio_mods_web::ModHash(String::new()),
+	}
+}
+
+fn summary(info: &factorio_mods_local::ModInfo) -> factorio_mods_web::ModSummary {
+	factorio_mods_web::ModSummary(info.description.as_ref().map_or_else(String::new, |description| description.0.clone()))
+}
+
+fn json<T>(value: &T, is_head: bool) -> http::Response<hyper::Body> where T: serde::Serialize {
+	let Ok(body) = serde_json::to_vec(value) else {
+		return status(http::StatusCode::INTERNAL_SERVER_ERROR);
+	};
+
+	http::Response::builder()
+	.header(http::header::CONTENT_TYPE, "application/json")
+	.header(http::header::CONTENT_LENGTH, body.len())
+	.body(if is_head { hyper::Body::empty() } else { body.into() })
+	.expect("hard-coded response must be valid")
+}
+
+/// Responds with the given release's zip file, or the part of it that the given `Range` header asks for.
+fn file(release: &Release, range: Option<&str>, is_head: bool) -> http::Response<hyper::Body> {
+	const CHUNK_LEN: usize = 64 * 1024;
+
+	let len = release.len;
+
+	let (status_code, range) = match parse_range(range, len) {
+		ByteRange::Full => (http::StatusCode::OK, 0..len),
+		ByteRange::Partial(range) => (http::StatusCode::PARTIAL_CONTENT, range),
+		ByteRange::Unsatisfiable =>
+			return
+				http::Response::builder()
+				.status(http::StatusCode::RANGE_NOT_SATISFIABLE)
+				.header(http::header::CONTENT_RANGE, format!("bytes */{len}"))
+				.body(hyper::Body::empty())
+				.expect("hard-coded response must be valid"),
+	};
+
+	let mut response =
+		http::Response::builder()
+		.status(status_code)
+		.header(http::header::CONTENT_TYPE, "application/zip")
+		.header(http::header::CONTENT_LENGTH, range.end - range.start)
+		.header(http::header::ACCEPT_RANGES, "bytes");
+	if status_code == http::StatusCode::PARTIAL_CONTENT {
+		response = response.header(http::header::CONTENT_RANGE, format!("bytes {}-{}/{len}", range.start, range.end - 1));
+	}
+
+	if is_head {
+		return response.body(hyper::Body::empty()).expect("hard-coded response must be valid");
+	}
+
+	let file = std::fs::File::open(&release.path).and_then(|mut file| {
+		std::io::Seek::seek(&mut file, std::io::SeekFrom::Start(range.start))?;
+		Ok(file)
+	});
+	let Ok(file) = file else {
+		return status(http::StatusCode::INTERNAL_SERVER_ERROR);
+	};
+
+	// The file is read in chunks as the client consumes it, since clients like `WebReader` only read the start of the range.
+	let chunks = futures_util::stream::try_unfold((file, range.end - range.start), |(mut file, remaining)| async move {
+		if remaining == 0 {
+			return Ok(None);
+		}
+
+		let mut chunk = vec![0; std::cmp::min(remaining, CHUNK_LEN as u64) as usize];
+		std::io::Read::read_exact(&mut file, &mut chunk)?;
+		let remaining = remaining - chunk.len() as u64;
+		Ok::<_, std::io::Error>(Some((bytes::Bytes::from(chunk), (file, remaining))))
+	});
+
+	response.body(hyper::Body::wrap_stream(chunks)).expect("hard-coded response must be valid")
+}
+
+fn status(status_code: http::StatusCode) -> http::Response<hyper::Body> {
+	http::Response::builder().status(status_code).body(hyper::Body::empty()).expect("hard-coded response must be valid")
+}
+
+/// The part of a file that a `Range` header asks for.
+#[derive(Debug, Eq, PartialEq)]
+enum ByteRange {
+	Full,
+	Partial(std::ops::Range<u64>),
+	Unsatisfiable,
+}
+
+/// Parses the given `Range` header for a file of the given length.
+///
+/// Only single byte ranges are supported. Other ranges, and malformed ones, are ignored as the HTTP spec allows, so the whole file is returned.
+fn parse_range(range: Option<&str>, len: u64) -> ByteRange {
+	let Some((start, end)) =
+		range
+		.and_then(|range| range.trim().strip_prefix("bytes="))
+		.filter(|range| !range.contains(','))
+		.and_then(|range| range.split_once('-'))
+	else {
+		return ByteRange::Full;
+	};
+
+	let (start, end) = match (start.trim(), end.trim()) {
+		("", suffix_len) => {
+			let Ok(suffix_len) = suffix_len.parse::<u64>() else { return ByteRange::Full; };
+			if suffix_len == 0 {
+				return ByteRange::Unsatisfiable;
+			}
+			(len.saturating_sub(suffix_len), len)
+		},
+
+		(start, "") => {
+			let Ok(start) = start.parse() else { return ByteRange::Full; };
+			(start, len)
+		},
+
+		(start, end) => {
+			let (Ok(start), Ok(end)) = (start.parse(), end.parse::<u64>()) else { return ByteRange::Full; };
+			if end < start {
+				return ByteRange::Full;
+			}
+			(start, std::cmp::min(end.saturating_add(1), len))
+		},
+	};
+
+	if start >= len {
+		return ByteRange::Unsatisfiable;
+	}
+
+	ByteRange::Partial(start..end)
+}
+
+/// Formats the given time like the mods portal does, eg `2020-01-02T03:04:05.000000Z`
+fn date_time(time: std::time::SystemTime) -> factorio_mods_web::DateTime {
+	let secs = time.duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+	let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+
+	// Converts days since the Unix epoch to a proleptic Gregorian date. See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+	let days = days + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days.rem_euclid(146_097);
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+	let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+	factorio_mods_web::DateTime(format!(
+		"{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.000000Z",
+		secs / 3600, secs % 3600 / 60, secs % 60,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	#[tokio::test]
+	async fn test_handle() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+		let foo_path = directory.join("foo_1.1.0.zip");
+		let bar_path = directory.join("bar_2.0.0.zip");
+		crate::test_util::write_mod_zip(&directory.join("foo_1.0.0.zip"), "foo", "1.0.0", &[]);
+		crate::test_util::write_mod_zip(&foo_path, "foo", "1.1.0", &[]);
+		crate::test_util::write_mod_zip(&bar_path, "bar", "2.0.0", &["foo >= 1.1"]);
+
+		// foo was updated after bar.
+		std::fs::File::options().write(true).open(&foo_path).unwrap().set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000)).unwrap();
+		std::fs::File::options().write(true).open(&bar_path).unwrap().set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000)).unwrap();
+
+		let catalog = super::Catalog::new(vec![directory.join("foo_1.0.0.zip"), foo_path.clone(), bar_path, directory.join("missing.zip")]);
+
+		let foo = std::fs::read(&foo_path).unwrap();
+		let len = foo.len();
+
+		let handle = |method, uri, range| request(&catalog, method, uri, range);
+
+		let names = |body: &[u8]| -> Vec<String> {
+			let response: serde_json::Value = serde_json::from_slice(body).unwrap();
+			response["results"].as_array().unwrap().iter().map(|mod_| mod_["name"].as_str().unwrap().to_owned()).collect()
+		};
+
+		let (status, _, body) = handle(http::Method::GET, "/api/mods?page_size=max", None).await;
+		assert_eq!(status, http::StatusCode::OK);
+		assert_eq!(names(&body), ["bar", "foo"]);
+
+		let (status, _, body) = handle(http::Method::GET, "/api/mods?page_size=max&sort=updated_at&sort_order=desc", None).await;
+		assert_eq!(status, http::StatusCode::OK);
+		assert_eq!(names(&body), ["foo", "bar"]);
+
+		let (status, _, body) = handle(http::Method::GET, "/api/mods/foo/full", None).await;
+		assert_eq!(status, http::StatusCode::OK);
+		let mod_: factorio_mods_web::Mod = serde_json::from_slice(&body).unwrap();
+		let releases: Vec<_> = mod_.releases.iter().map(|release| (release.version.to_string(), &*release.download_url.0)).collect();
+		assert_eq!(releases, [("1.0.0".to_owned(), "download/foo/1.0.0"), ("1.1.0".to_owned(), "download/foo/1.1.0")]);
+
+		let (status, _, _) = handle(http::Method::GET, "/api/mods/baz", None).await;
+		assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+		let (status, (content_length, content_range), body) = handle(http::Method::GET, "/download/foo/1.1.0", None).await;
+		assert_eq!(status, http::StatusCode::OK);
+		assert_eq!(content_length, Some(len.to_string()));
+		assert_eq!(content_range, None);
+		assert_eq!(body, foo);
+
+		let (status, (content_length, _), body) = handle(http::Method::HEAD, "/download/foo/1.1.0", None).await;
+		assert_eq!(status, http::StatusCode::OK);
+		assert_eq!(content_length, Some(len.to_string()));
+		assert!(body.is_empty());
+
+		let (status, (content_length, content_range), body) = handle(http::Method::GET, "/download/foo/1.1.0", Some("bytes=10-19")).await;
+		assert_eq!(status, http::StatusCode::PARTIAL_CONTENT);
+		assert_eq!(content_length.as_deref(), Some("10"));
+		assert_eq!(content_range, Some(format!("bytes 10-19/{len}")));
+		assert_eq!(body, foo[10..20]);
+
+		let range = format!("bytes={len}-");
+		let (status, (_, content_range), body) = handle(http::Method::GET, "/download/foo/1.1.0", Some(&range)).await;
+		assert_eq!(status, http::StatusCode::RANGE_NOT_SATISFIABLE);
+		assert_eq!(content_range, Some(format!("bytes */{len}")));
+		assert!(body.is_empty());
+
+		let (status, _, _) = handle(http::Method::GET, "/download/foo/2.0.0", None).await;
+		assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+		let (status, _, _) = handle(http::Method::POST, "/api/mods", None).await;
+		assert_eq!(status, http::StatusCode::METHOD_NOT_ALLOWED);
+	}
+
+	/// Sends a request to `handle`, and returns the response's status, `Content-Length` and `Content-Range` headers, and body.
+	async fn request(
+		catalog: &super::Catalog,
+		method: http::Method,
+		uri: &str,
+		range: Option<&str>,
+	) -> (http::StatusCode, (Option<String>, Option<String>), bytes::Bytes) {
+		let mut request = http::Request::builder().method(method).uri(uri);
+		if let Some(range) = range {
+			request = request.header(http::header::RANGE, range);
+		}
+		let response = super::handle(catalog, &request.body(hyper::Body::empty()).unwrap());
+
+		let (parts, body) = response.into_parts();
+		let header = |name| parts.headers.get(name).map(|value: &http::HeaderValue| value.to_str().unwrap().to_owned());
+		let headers = (header(http::header::CONTENT_LENGTH), header(http::header::CONTENT_RANGE));
+		(parts.status, headers, hyper::body::to_bytes(body).await.unwrap())
+	}
+
+	#[test]
+	fn test_parse_range() {
+		use super::ByteRange;
+
+		assert_eq!(super::parse_range(None, 100), ByteRange::Full);
+		assert_eq!(super::parse_range(Some("bytes=10-"), 100), ByteRange::Partial(10..100));
+		assert_eq!(super::parse_range(Some("bytes=10-19"), 100), ByteRange::Partial(10..20));
+		assert_eq!(super::parse_range(Some("bytes=90-200"), 100), ByteRange::Partial(90..100));
+		assert_eq!(super::parse_range(Some("bytes=-30"), 100), ByteRange::Partial(70..100));
+		assert_eq!(super::parse_range(Some("bytes=-300"), 100), ByteRange::Partial(0..100));
+		assert_eq!(super::parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+		assert_eq!(super::parse_range(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+		assert_eq!(super::parse_range(Some("bytes=0-9,20-29"), 100), ByteRange::Full);
+		assert_eq!(super::parse_range(Some("bytes=20-10"), 100), ByteRange::Full);
+		assert_eq!(super::parse_range(Some("items=0-9"), 100), ByteRange::Full);
+	}
+
+	#[test]
+	fn test_date_time() {
+		let date_time = |secs| super::date_time(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs)).0;
+
+		assert_eq!(date_time(0), "1970-01-01T00:00:00.000000Z");
+		assert_eq!(date_time(951_782_400), "2000-02-29T00:00:00.000000Z");
+		assert_eq!(date_time(1_700_000_000), "2023-11-14T22:13:20.000000Z");
+	}
+}
@@ -0,0 +1,415 @@
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[command(subcommand)]
+	subcommand: SettingsSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum SettingsSubCommand {
+	#[command(name = "clean", about = "Remove the settings of mods that aren't installed")]
+	Clean,
+
+	#[command(name = "export", about = "Export mod settings as JSON")]
+	Export {
+		#[arg(help = "Only export the settings of these mods", long = "mod")]
+		mods: Vec<factorio_mods_common::ModName>,
+
+		#[arg(help = "Write the settings to this file instead of stdout", short = 'o', long = "output", value_parser)]
+		output: Option<std::path::PathBuf>,
+	},
+
+	#[command(name = "get", about = "Show the value of a mod setting")]
+	Get {
+		#[arg(help = "name of the setting")]
+		name: String,
+	},
+
+	#[command(name = "import", about = "Import mod settings from JSON exported by `fac settings export`")]
+	Import {
+		#[arg(help = "file to import the settings from", value_parser)]
+		path: std::path::PathBuf,
+	},
+
+	#[command(name = "list", about = "List mod settings, grouped by mod")]
+	List {
+		#[arg(help = "Only list the settings of these mods", long = "mod")]
+		mods: Vec<factorio_mods_common::ModName>,
+	},
+
+	#[command(name = "reset", about = "Reset mod settings to their defaults")]
+	Reset {
+		#[arg(help = "names of the settings to reset", required_unless_present = "mods")]
+		names: Vec<String>,
+
+		#[arg(help = "Reset all the settings of these mods", long = "mod")]
+		mods: Vec<factorio_mods_common::ModName>,
+	},
+
+	#[command(name = "set", about = "Change the value of a mod setting")]
+	Set {
+		#[arg(help = "name of the setting")]
+		name: String,
+
+		#[arg(help = "new value of the setting, as JSON. Values that aren't valid JSON are treated as strings.")]
+		value: String,
+
+		#[arg(
+			help = "type of the setting, one of startup, runtime-global or runtime-per-user. Required if the setting doesn't exist yet.",
+			long = "type",
+		)]
+		setting_type: Option<factorio_mods_local::SettingType>,
+	},
+}
+
+impl SubCommand {
+	pub(crate) fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		prompt_override: Option<bool>,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let mut mod_settings = local_api.mod_settings().context("could not read mod settings")?;
+
+		match self.subcommand {
+			SettingsSubCommand::Clean => {
+				let owners = setting_owners(local_api, &mod_settings)?;
+
+				let orphaned: Vec<_> =
+					mod_settings.settings()
+					.filter(|(_, name, _)| !owners.contains_key(*name))
+					.map(|(setting_type, name, _)| (setting_type, name.to_owned()))
+					.collect();
+				if orphaned.is_empty() {
+					println!("Nothing to do.");
+					return Ok(());
+				}
+
+				println!("The following settings don't appear to belong to any installed mod and will be removed:");
+				for (setting_type, name) in &orphaned {
+					println!("    {name} ({setting_type})");
+				}
+				println!();
+
+				if !crate::util::prompt_continue(prompt_override)? {
+					return Ok(());
+				}
+
+				for (_, name) in orphaned {
+					mod_settings.remove(&name);
+				}
+
+				local_api.save_mod_settings(&mod_settings).context("could not save mod settings")?;
+			},
+
+			SettingsSubCommand::Export { mods, output } => {
+				let owners = setting_owners(local_api, &mod_settings)?;
+
+				let exported = export(&mod_settings, |name| mods.is_empty() || owners.get(name).is_some_and(|owner| mods.contains(owner)));
+
+				if let Some(output) = output {
+					let file = std::fs::File::create(&output).with_context(|| format!("could not create {}", output.display()))?;
+					serde_json::to_writer_pretty(file, &exported).with_context(|| format!("could not write {}", output.display()))?;
+				}
+				else {
+					println!("{}", serde_json::to_string_pretty(&exported).context("could not serialize mod settings")?);
+				}
+			},
+
+			SettingsSubCommand::Get { name } => {
+				let Some((setting_type, value)) = mod_settings.get(&name) else {
+					println!("Setting {name} is not set. The game will use the mod's default value.");
+					return Ok(());
+				};
+
+				println!("{name} ({setting_type}) = {}", to_json(value));
+			},
+
+			SettingsSubCommand::Import { path } => {
+				let file = std::fs::File::open(&path).with_context(|| format!("could not open {}", path.display()))?;
+				let imported: std::collections::BTreeMap<String, serde_json::Map<String, serde_json::Value>> =
+					serde_json::from_reader(std::io::BufReader::new(file))
+					.with_context(|| format!("could not parse {}", path.display()))?;
+
+				let changes = import(&mod_settings, imported).with_context(|| format!("could not parse {}", path.display()))?;
+
+				if changes.is_empty() {
+					println!("Nothing to do.");
+					return Ok(());
+				}
+
+				println!("The following settings will be changed:");
+				for (setting_type, name, value) in &changes {
+					println!("    {name} ({setting_type}) = {}", to_json(value));
+				}
+				println!();
+
+				if !crate::util::prompt_continue(prompt_override)? {
+					return Ok(());
+				}
+
+				for (setting_type, name, value) in changes {
+					mod_settings.set(setting_type, &name, value);
+				}
+
+				local_api.save_mod_settings(&mod_settings).context("could not save mod settings")?;
+			},
+
+			SettingsSubCommand::List { mods } => {
+				let owners = setting_owners(local_api, &mod_settings)?;
+
+				let mut settings_by_owner: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+				for (setting_type, name, value) in mod_settings.settings() {
+					let owner = owners.get(name);
+					if !mods.is_empty() && !owner.is_some_and(|owner| mods.contains(owner)) {
+						continue;
+					}
+
+					settings_by_owner.entry(owner).or_default().push((setting_type, name, value));
+				}
+
+				if settings_by_owner.is_empty() {
+					println!("No settings found.");
+					return Ok(());
+				}
+
+				for (owner, mut settings) in settings_by_owner {
+					match owner {
+						Some(owner) => println!("{owner}:"),
+						None => println!("Not belonging to any installed mod:"),
+					}
+
+					settings.sort_by(|(setting_type1, name1, _), (setting_type2, name2, _)| setting_type1.cmp(setting_type2).then_with(|| name1.cmp(name2)));
+					for (setting_type, name, value) in settings {
+						println!("    {name} ({setting_type}) = {}", to_json(value));
+					}
+
+					println!();
+				}
+			},
+
+			SettingsSubCommand::Reset { mut names, mods } => {
+				if !mods.is_empty() {
+					let owners = setting_owners(local_api, &mod_settings)?;
+					names.extend(
+						mod_settings.settings()
+						.filter(|(_, name, _)| owners.get(*name).is_some_and(|owner| mods.contains(owner)))
+						.map(|(_, name, _)| name.to_owned()));
+				}
+
+				names.sort();
+				names.dedup();
+
+				let mut removed = vec![];
+				for name in names {
+					match mod_settings.get(&name) {
+						Some((setting_type, _)) => removed.push((setting_type, name)),
+						None => println!("Setting {name} is not set."),
+					}
+				}
+
+				if removed.is_empty() {
+					println!("Nothing to do.");
+					return Ok(());
+				}
+
+				println!("The following settings will be reset to their defaults:");
+				for (setting_type, name) in &removed {
+					println!("    {name} ({setting_type})");
+				}
+				println!();
+
+				if !crate::util::prompt_continue(prompt_override)? {
+					return Ok(());
+				}
+
+				for (_, name) in removed {
+					mod_settings.remove(&name);
+				}
+
+				local_api.save_mod_settings(&mod_settings).context("could not save mod settings")?;
+			},
+
+			SettingsSubCommand::Set { name, value, setting_type } => {
+				let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+
+				let existing = mod_settings.get(&name);
+				let Some(setting_type) = setting_type.or_else(|| existing.map(|(setting_type, _)| setting_type)) else {
+					anyhow::bail!("setting {name} is not set yet, so its type must be specified with --type");
+				};
+
+				let value = from_json(value, existing.map(|(_, existing)| existing));
+				if existing == Some((setting_type, &value)) {
+					println!("Nothing to do.");
+					return Ok(());
+				}
+
+				println!("The following setting will be changed:");
+				println!("    {name} ({setting_type}) = {}", to_json(&value));
+				println!();
+
+				if !crate::util::prompt_continue(prompt_override)? {
+					return Ok(());
+				}
+
+				mod_settings.set(setting_type, &name, value);
+
+				local_api.save_mod_settings(&mod_settings).context("could not save mod settings")?;
+			},
+		}
+
+		Ok(())
+	}
+}
+
+fn setting_owners(
+	local_api: &factorio_mods_local::Api,
+	mod_settings: &factorio_mods_local::ModSettings,
+) -> anyhow::Result<std::collections::BTreeMap<String, factorio_mods_common::ModName>> {
+	use anyhow::Context;
+
+	local_api.setting_owners(mod_settings.settings().map(|(_, name, _)| name))
+		.context("could not determine which mods the settings belong to")
+}
+
+/// Converts the settings whose names `include` accepts to JSON, grouped by setting type.
+fn export(mod_settings: &factorio_mods_local::ModSettings, mut include: impl FnMut(&str) -> bool) -> serde_json::Value {
+	let mut exported = serde_json::Map::new();
+	for setting_type in factorio_mods_local::SettingType::ALL {
+		exported.insert(setting_type.name().to_owned(), serde_json::Value::Object(Default::default()));
+	}
+
+	for (setting_type, name, value) in mod_settings.settings() {
+		if !include(name) {
+			continue;
+		}
+
+		let serde_json::Value::Object(settings) = exported.get_mut(setting_type.name()).unwrap() else { unreachable!() };
+		settings.insert(name.to_owned(), to_json(value));
+	}
+
+	serde_json::Value::Object(exported)
+}
+
+/// Returns the settings that importing the given JSON from [`export`] would change, with their new values.
+fn import(
+	mod_settings: &factorio_mods_local::ModSettings,
+	imported: std::collections::BTreeMap<String, serde_json::Map<String, serde_json::Value>>,
+) -> anyhow::Result<Vec<(factorio_mods_local::SettingType, String, factorio_mods_local::PropertyTree)>> {
+	let mut changes = vec![];
+
+	for (setting_type, settings) in imported {
+		let setting_type: factorio_mods_local::SettingType = setting_type.parse().map_err(|err: String| anyhow::anyhow!(err))?;
+
+		for (name, value) in settings {
+			let existing = mod_settings.get(&name).map(|(_, existing)| existing);
+			let value = from_json(value, existing);
+			if existing != Some(&value) {
+				changes.push((setting_type, name, value));
+			}
+		}
+	}
+
+	Ok(changes)
+}
+
+fn to_json(value: &factorio_mods_local::PropertyTree) -> serde_json::Value {
+	match value {
+		factorio_mods_local::PropertyTree::None => serde_json::Value::Null,
+		factorio_mods_local::PropertyTree::Bool(value) => serde_json::Value::Bool(*value),
+		factorio_mods_local::PropertyTree::Number(value) => serde_json::Number::from_f64(*value).map_or(serde_json::Value::Null, serde_json::Value::Number),
+		factorio_mods_local::PropertyTree::String(value) => serde_json::Value::String(value.clone()),
+		factorio_mods_local::PropertyTree::List(list) => serde_json::Value::Array(list.iter().map(to_json).collect()),
+		factorio_mods_local::PropertyTree::Dictionary(dictionary) =>
+			serde_json::Value::Object(dictionary.iter().map(|(key, value)| (key.clone(), to_json(value))).collect()),
+		factorio_mods_local::PropertyTree::SignedInteger(value) => serde_json::Value::Number((*value).into()),
+		factorio_mods_local::PropertyTree::UnsignedInteger(value) => serde_json::Value::Number((*value).into()),
+	}
+}
+
+/// Converts a JSON value to a property tree.
+///
+/// Numbers are converted to the same kind of number as the existing value of the setting if possible.
+/// Otherwise numbers written as integers, like `5`, become integers, and other numbers, like `5.0` or `0.5`, become floating point numbers.
+/// [`to_json`] writes floating point numbers with a fractional part, so exported settings are imported as the same kind of number.
+fn from_json(value: serde_json::Value, existing: Option<&factorio_mods_local::PropertyTree>) -> factorio_mods_local::PropertyTree {
+	match value {
+		serde_json::Value::Null => factorio_mods_local::PropertyTree::None,
+
+		serde_json::Value::Bool(value) => factorio_mods_local::PropertyTree::Bool(value),
+
+		serde_json::Value::Number(value) => match (existing, value.as_i64(), value.as_u64()) {
+			(Some(factorio_mods_local::PropertyTree::UnsignedInteger(_)), _, Some(value)) => factorio_mods_local::PropertyTree::UnsignedInteger(value),
+			(Some(factorio_mods_local::PropertyTree::Number(_)), _, _) | (_, None, None) => factorio_mods_local::PropertyTree::Number(value.as_f64().unwrap_or_default()),
+			(_, Some(value), _) => factorio_mods_local::PropertyTree::SignedInteger(value),
+			(_, None, Some(value)) => factorio_mods_local::PropertyTree::UnsignedInteger(value),
+		},
+
+		serde_json::Value::String(value) => factorio_mods_local::PropertyTree::String(value),
+
+		serde_json::Value::Array(list) =>
+			factorio_mods_local::PropertyTree::List(list.into_iter().map(|value| from_json(value, None)).collect()),
+
+		serde_json::Value::Object(dictionary) =>
+			factorio_mods_local::PropertyTree::Dictionary(
+				dictionary.into_iter()
+				.map(|(key, value)| {
+					let existing = existing.and_then(|existing| existing.get(&key));
+					let value = from_json(value, existing);
+					(key, value)
+				})
+				.collect()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_export_import() {
+		let game_version = "1.1.100".parse().unwrap();
+
+		let mut mod_settings = factorio_mods_local::ModSettings::new(&game_version);
+		mod_settings.set(factorio_mods_local::SettingType::Startup, "int-setting", factorio_mods_local::PropertyTree::SignedInteger(5));
+		mod_settings.set(factorio_mods_local::SettingType::Startup, "double-setting", factorio_mods_local::PropertyTree::Number(5.0));
+		mod_settings.set(factorio_mods_local::SettingType::RuntimeGlobal, "fraction-setting", factorio_mods_local::PropertyTree::Number(0.25));
+		mod_settings.set(factorio_mods_local::SettingType::RuntimeGlobal, "bool-setting", factorio_mods_local::PropertyTree::Bool(true));
+		mod_settings.set(factorio_mods_local::SettingType::RuntimePerUser, "string-setting", factorio_mods_local::PropertyTree::String("foo".to_owned()));
+		mod_settings.set(
+			factorio_mods_local::SettingType::RuntimePerUser,
+			"color-setting",
+			// JSON objects are exported with their keys sorted, so they're sorted here too.
+			factorio_mods_local::PropertyTree::Dictionary(vec![
+				("g".to_owned(), factorio_mods_local::PropertyTree::Number(0.5)),
+				("r".to_owned(), factorio_mods_local::PropertyTree::Number(1.0)),
+			]),
+		);
+
+		// Export to a string and back, like `fac settings export -o` and `fac settings import` do.
+		let exported = serde_json::to_string(&super::export(&mod_settings, |_| true)).unwrap();
+		let imported = serde_json::from_str(&exported).unwrap();
+
+		let mut imported_mod_settings = factorio_mods_local::ModSettings::new(&game_version);
+		for (setting_type, name, value) in super::import(&imported_mod_settings, imported).unwrap() {
+			imported_mod_settings.set(setting_type, &name, value);
+		}
+
+		let settings = |mod_settings: &factorio_mods_local::ModSettings| {
+			let mut settings: Vec<_> = mod_settings.settings().map(|(setting_type, name, value)| (setting_type, name.to_owned(), value.clone())).collect();
+			settings.sort_by(|(_, name1, _), (_, name2, _)| name1.cmp(name2));
+			settings
+		};
+		assert_eq!(settings(&imported_mod_settings), settings(&mod_settings));
+	}
+
+	#[test]
+	fn test_from_json() {
+		let from_json = |value: &str, existing| super::from_json(serde_json::from_str(value).unwrap(), existing);
+
+		assert_eq!(from_json("5", None), factorio_mods_local::PropertyTree::SignedInteger(5));
+		assert_eq!(from_json("-5", None), factorio_mods_local::PropertyTree::SignedInteger(-5));
+		assert_eq!(from_json("5.0", None), factorio_mods_local::PropertyTree::Number(5.0));
+		assert_eq!(from_json("5", Some(&factorio_mods_local::PropertyTree::Number(1.0))), factorio_mods_local::PropertyTree::Number(5.0));
+		assert_eq!(from_json("5", Some(&factorio_mods_local::PropertyTree::UnsignedInteger(1))), factorio_mods_local::PropertyTree::UnsignedInteger(5));
+		assert_eq!(from_json("0.5", Some(&factorio_mods_local::PropertyTree::SignedInteger(1))), factorio_mods_local::PropertyTree::Number(0.5));
+	}
+}
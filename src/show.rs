@@ -2,23 +2,54 @@
 pub(crate) struct SubCommand {
 	#[arg(help = "mods to show", required = true)]
 	names: Vec<factorio_mods_common::ModName>,
+
+	#[arg(help = "Also show the changelog of the latest release", long = "changelog", conflicts_with = "installed")]
+	changelog: bool,
+
+	#[arg(help = "Show the locally installed copies of the mods instead of querying the mods database", long = "installed")]
+	installed: bool,
+
+	#[arg(help = "Also compare the locally installed copies with the newest compatible releases in the mods database", long = "compare", requires = "installed")]
+	compare: bool,
 }
 
 impl SubCommand {
 	pub(crate) async fn run(
 		self,
+		local_api: anyhow::Result<factorio_mods_local::Api>,
 		web_api: &factorio_mods_web::Api,
+		prompt_override: Option<bool>,
 	) -> anyhow::Result<()> {
 		use anyhow::Context;
 
+		if self.installed {
+			return self.run_installed(&local_api?, web_api).await;
+		}
+
 		let textwrap_options = crate::textwrap_options();
 
+		// The local API is only used to check releases against the installed game version and to download changelogs,
+		// so a missing local installation is only an error if changelogs are requested.
+		let game_version = local_api.as_ref().ok().map(|local_api| local_api.game_version().clone());
+
+		// Downloading the changelog requires the user's credentials.
+		let user_credentials =
+			if self.changelog {
+				let local_api = local_api?;
+				Some(std::rc::Rc::new(crate::util::ensure_user_credentials(&local_api, web_api, prompt_override).await?))
+			}
+			else {
+				None
+			};
+
 		let mut mods: futures_util::stream::FuturesOrdered<_> =
 			self.names.into_iter().map(|name| async move {
-				web_api.get(&name).await
+				web_api.get_full(&name).await
 				.with_context(|| format!("could not retrieve mod {name}"))
 			}).collect();
 
+		let mut descriptions = vec![];
+
 		while let Some(mod_) = futures_util::TryStreamExt::try_next(&mut mods).await? {
 			println!("Name: {}", mod_.name);
 			println!("Author: {}", itertools::join(mod_.owner, ", "));
@@ -31,7 +62,55 @@ impl SubCommand {
 				}
 			}
 
-			let releases = mod_.releases;
+			if let Some(description) = &mod_.description {
+				println!("Description:");
+
+				for line in description.0.lines() {
+					for line in textwrap::wrap(line, textwrap_options.clone()) {
+						println!("{line}");
+					}
+				}
+			}
+
+			if let Some(category) = &mod_.category {
+				println!("Category: {category}");
+			}
+
+			if !mod_.tags.is_empty() {
+				println!("Tags: {}", itertools::join(&mod_.tags, ", "));
+			}
+
+			if let Some(license) = &mod_.license {
+				match &license.url {
+					Some(url) => println!("License: {} ({url})", license.title),
+					None => println!("License: {}", license.title),
+				}
+			}
+
+			if let Some(homepage) = &mod_.homepage {
+				println!("Homepage: {homepage}");
+			}
+
+			if let Some(source_url) = &mod_.source_url {
+				println!("Source: {source_url}");
+			}
+
+			if let Some(created_at) = &mod_.created_at {
+				println!("Created: {}", date(created_at));
+			}
+
+			if let Some(updated_at) = &mod_.updated_at {
+				println!("Updated: {}", date(updated_at));
+			}
+
+			if mod_.deprecated {
+				println!("Deprecated: yes");
+			}
+
+			println!("Downloads: {}", mod_.downloads_count);
+
+			let mut releases = mod_.releases;
+			releases.sort_by(|release1, release2| release1.version.cmp(&release2.version));
 
 			if releases.is_empty() {
 				println!("Releases:");
@@ -45,18 +124,251 @@ impl SubCommand {
 				println!("Game versions: {}", itertools::join(game_versions, ", "));
 
 				println!("Releases:");
-				for release in releases {
+				for release in &releases {
+					let compatibility = match &game_version {
+						Some(game_version) => {
+							let game_version_req = factorio_mods_common::VersionReqMatcher {
+								version_req: &release.info_json.factorio_version.0,
+								is_base: true,
+							};
+							if package::VersionReq::matches(&game_version_req, game_version) {
+								" (compatible)"
+							}
+							else {
+								" (incompatible)"
+							}
+						},
+
+						None => "",
+					};
+
 					println!(
-						"    Version: {} Game version: {}",
+						"    Version: {} Game version: {} Released: {}{compatibility}",
 						format_args!("{:-9}", release.version),
 						format_args!("{:-9}", release.info_json.factorio_version),
+						date(&release.released_at),
 					);
+
+					let dependencies: Vec<_> =
+						release.info_json.dependencies.iter()
+						.filter(|dep| dep.name.0 != "base")
+						.map(ToString::to_string)
+						.collect();
+					if !dependencies.is_empty() {
+						println!("        Dependencies: {}", dependencies.join(", "));
+					}
+				}
+			}
+
+			if let (Some(user_credentials), Some(latest_release)) = (&user_credentials, releases.pop()) {
+				let changelog =
+					crate::solve::get_changelog(web_api, std::rc::Rc::new(latest_release), user_credentials.clone()).await
+					.with_context(|| format!("could not get changelog of mod {}", mod_.name))?;
+
+				println!("Changelog:");
+				match changelog {
+					Some(changelog) => changelog.print(|_| true),
+					None => println!("    No changelog"),
 				}
 			}
 
 			println!();
+
+			if let Some(description) = mod_.description {
+				descriptions.push((mod_.name, description));
+			}
+		}
+
+		// Descriptions aren't part of the mods list that the search index is built from, so save them in the index now that they're known.
+		if !descriptions.is_empty() {
+			if let Some(mut index) = crate::search_index::SearchIndex::load_existing(web_api)? {
+				for (name, description) in descriptions {
+					index.set_description(&name, description.0);
+				}
+				index.save_default(web_api)?;
+			}
 		}
 
 		Ok(())
 	}
 }
+
+impl SubCommand {
+	async fn run_installed(
+		self,
+		local_api: &factorio_mods_local::Api,
+		web_api: &factorio_mods_web::Api,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let textwrap_options = crate::textwrap_options();
+
+		let mods_status = local_api.mods_status().context("could not parse installed mods status")?;
+
+		let mut all_installed_mods: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+		for mod_ in local_api.installed_mods().context("could not enumerate installed mods")? {
+			let mod_ = mod_.context("could not process an installed mod")?;
+			all_installed_mods.entry(mod_.info.name.clone()).or_default().push(mod_);
+		}
+
+		for name in self.names {
+			let Some(mut installed_mods) = all_installed_mods.remove(&name) else {
+				println!("Mod {name} is not installed.");
+				println!();
+				continue;
+			};
+
+			installed_mods.sort_by(|mod1, mod2| mod1.info.version.cmp(&mod2.info.version));
+
+			let enabled = mods_status.get(&name).copied().unwrap_or(true);
+
+			for installed_mod in &installed_mods {
+				let info = &installed_mod.info;
+
+				println!("Name: {}", info.name);
+				println!("Version: {}", info.version);
+				println!("Author: {}", itertools::join(&info.author, ", "));
+				println!("Title: {}", info.title);
+
+				if let Some(description) = &info.description {
+					println!("Description:");
+
+					for line in description.0.lines() {
+						for line in textwrap::wrap(line, textwrap_options.clone()) {
+							println!("{line}");
+						}
+					}
+				}
+
+				if let Some(homepage) = &info.homepage {
+					println!("Homepage: {homepage}");
+				}
+
+				println!("Game version: {}", info.factorio_version);
+
+				let dependencies: Vec<_> = info.dependencies.iter().map(ToString::to_string).collect();
+				println!("Dependencies: {}", if dependencies.is_empty() { "none".to_owned() } else { dependencies.join(", ") });
+
+				println!("Path: {}", installed_mod.path.display());
+
+				let (mod_type, size) = match installed_mod.mod_type {
+					factorio_mods_local::InstalledModType::Zipped => (
+						"zipped",
+						std::fs::metadata(&installed_mod.path).map(|metadata| metadata.len()),
+					),
+					factorio_mods_local::InstalledModType::Unpacked => (
+						"unpacked",
+						directory_size(&installed_mod.path),
+					),
+				};
+				let size = size.with_context(|| format!("could not compute size of {}", installed_mod.path.display()))?;
+				println!("Type: {mod_type}");
+				println!("Size: {size} bytes");
+
+				println!("Enabled: {}", if enabled { "yes" } else { "no" });
+
+				println!();
+			}
+
+			if self.compare {
+				let installed_mod = installed_mods.last().unwrap();
+
+				let mod_ = web_api.get_full(&name).await.with_context(|| format!("could not retrieve mod {name}"))?;
+				compare(installed_mod, &mod_, local_api.game_version());
+
+				println!();
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Prints the differences between the given installed mod and the newest release of the mod that is compatible with the given game version.
+fn compare(installed_mod: &factorio_mods_local::InstalledMod, mod_: &factorio_mods_web::Mod, game_version: &factorio_mods_common::ReleaseVersion) {
+	let newest_compatible_release =
+		mod_.releases.iter()
+		.filter(|release| {
+			let game_version_req = factorio_mods_common::VersionReqMatcher {
+				version_req: &release.info_json.factorio_version.0,
+				is_base: true,
+			};
+			package::VersionReq::matches(&game_version_req, game_version)
+		})
+		.max_by(|release1, release2| release1.version.cmp(&release2.version));
+
+	let Some(release) = newest_compatible_release else {
+		println!("Compared to the mods database: no release is compatible with game version {game_version}");
+		return;
+	};
+
+	let info = &installed_mod.info;
+
+	println!("Compared to the newest compatible release {} {} in the mods database:", mod_.name, release.version);
+
+	let mut differences = vec![];
+
+	match info.version.cmp(&release.version) {
+		std::cmp::Ordering::Less => differences.push(format!("Version: {} is older than {}", info.version, release.version)),
+		std::cmp::Ordering::Equal => (),
+		std::cmp::Ordering::Greater => differences.push(format!("Version: {} is newer than {}", info.version, release.version)),
+	}
+
+	if info.title != mod_.title {
+		differences.push(format!("Title: {:?} instead of {:?}", info.title.0, mod_.title.0));
+	}
+
+	if info.factorio_version != release.info_json.factorio_version {
+		differences.push(format!("Game version: {} instead of {}", info.factorio_version, release.info_json.factorio_version));
+	}
+
+	if let (Some(local_homepage), Some(remote_homepage)) = (&info.homepage, &mod_.homepage) {
+		if local_homepage != remote_homepage {
+			differences.push(format!("Homepage: {local_homepage} instead of {remote_homepage}"));
+		}
+	}
+
+	let local_dependencies: std::collections::BTreeSet<_> = info.dependencies.iter().map(ToString::to_string).collect();
+	let remote_dependencies: std::collections::BTreeSet<_> = release.info_json.dependencies.iter().map(ToString::to_string).collect();
+	for dependency in local_dependencies.difference(&remote_dependencies) {
+		differences.push(format!("Dependency: {dependency} is only in the installed copy"));
+	}
+	for dependency in remote_dependencies.difference(&local_dependencies) {
+		differences.push(format!("Dependency: {dependency} is only in the mods database"));
+	}
+
+	if differences.is_empty() {
+		println!("    No differences");
+	}
+	else {
+		for difference in differences {
+			println!("    {difference}");
+		}
+	}
+}
+
+/// Computes the total size of the files in the given directory, recursively.
+///
+/// Symlinks are not followed.
+fn directory_size(path: &std::path::Path) -> std::io::Result<u64> {
+	let mut size = 0;
+
+	for entry in std::fs::read_dir(path)? {
+		let entry = entry?;
+		let metadata = entry.path().symlink_metadata()?;
+		if metadata.is_dir() {
+			size += directory_size(&entry.path())?;
+		}
+		else {
+			size += metadata.len();
+		}
+	}
+
+	Ok(size)
+}
+
+/// Returns the date part of a date and time string returned by the web API.
+fn date(date_time: &factorio_mods_web::DateTime) -> &str {
+	date_time.0.split_once('T').map_or(&*date_time.0, |(date, _)| date)
+}
+
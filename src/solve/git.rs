@@ -0,0 +1,269 @@
+/// Mods whose config entries have git repositories, as a [`super::source::ModSource`]
+///
+/// Each mod has a single release, which is built from the repository at the entry's `rev` and cached.
+pub(super) struct GitModSource {
+	/// The `info.json`, commit and zip file of each mod.
+	built: std::collections::BTreeMap<factorio_mods_common::ModName, Built>,
+}
+
+/// A mod's zip file, built from its git repository
+#[derive(Debug)]
+struct Built {
+	info: factorio_mods_local::ModInfo,
+
+	/// The commit that the zip file was built from. This is also the zip file's comment.
+	commit: String,
+
+	path: std::path::PathBuf,
+}
+
+impl GitModSource {
+	/// Builds the given mods from their git repositories.
+	///
+	/// git runs synchronously, so this is done up front rather than while the solver has portal requests in flight.
+	pub(super) fn new(mods: &std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::GitSource>) -> anyhow::Result<Self> {
+		use anyhow::Context;
+
+		let mut cache_directory = dirs::cache_dir().context("could not derive path to cache directory")?;
+		cache_directory.push("fac");
+		cache_directory.push("git");
+
+		let built =
+			mods.iter()
+			.map(|(name, git)| {
+				let built = build(&cache_directory, name, git).with_context(|| format!("could not build {name} from its git repository"))?;
+				Ok((name.clone(), built))
+			})
+			.collect::<anyhow::Result<_>>()?;
+
+		Ok(GitModSource { built })
+	}
+}
+
+impl super::source::ModSource for GitModSource {
+	fn releases(&self, name: &factorio_mods_common::ModName) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<Vec<super::source::Release>>>> {
+		let result = self.built.get(name).map(|Built { info, .. }| vec![super::source::Release {
+			name: name.clone(),
+			version: info.version.clone(),
+			factorio_version: info.factorio_version.clone(),
+			filename: format!("{name}_{}.zip", info.version),
+			source: super::GIT_SOURCE.to_owned(),
+		}]);
+		Box::pin(futures_util::future::ok(result))
+	}
+
+	fn mod_info(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<factorio_mods_local::ModInfo>> {
+		let result =
+			self.built.get(&release.name).map(|Built { info, .. }| info.clone())
+			.ok_or_else(|| anyhow::anyhow!("{} {} was not built from its git repository", release.name, release.version));
+		Box::pin(futures_util::future::ready(result))
+	}
+
+	fn download(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::stream::LocalBoxStream<'_, anyhow::Result<bytes::Bytes>> {
+		use anyhow::Context;
+
+		let result =
+			self.built.get(&release.name).map(|Built { path, .. }| path.clone())
+			.ok_or_else(|| anyhow::anyhow!("{} {} was not built from its git repository", release.name, release.version))
+			.and_then(|path|
+				std::fs::read(&path)
+				.map(bytes::Bytes::from)
+				.with_context(|| format!("could not read file {}", path.display())));
+		Box::pin(futures_util::stream::once(futures_util::future::ready(result)))
+	}
+
+	fn is_installed_as(&self, release: &super::source::Release, installed_mod: &factorio_mods_local::InstalledMod) -> anyhow::Result<bool> {
+		use anyhow::Context;
+
+		// A commit that doesn't change the version in info.json still needs to be installed,
+		// so the commit that the installed zip was built from is compared with the current commit.
+		if installed_mod.mod_type != factorio_mods_local::InstalledModType::Zipped {
+			return Ok(false);
+		}
+
+		let Built { commit, .. } =
+			self.built.get(&release.name)
+			.with_context(|| format!("{} {} was not built from its git repository", release.name, release.version))?;
+
+		let installed_path_displayable = installed_mod.path.display();
+		let installed = std::fs::File::open(&installed_mod.path).with_context(|| format!("could not open file {installed_path_displayable}"))?;
+		let installed = zip::ZipArchive::new(installed).with_context(|| format!("could not parse file {installed_path_displayable}"))?;
+		Ok(installed.comment() == commit.as_bytes())
+	}
+}
+
+/// Clones or fetches the given mod's git repository into the cache directory, and builds the mod's zip file at the entry's `rev`.
+///
+/// Zip files are cached by commit, so an unchanged `rev` isn't built again.
+/// Like `git archive` of a commit, the zip file's comment is the commit it was built from, even if it's built from a subdirectory.
+fn build(
+	cache_directory: &std::path::Path,
+	name: &factorio_mods_common::ModName,
+	git: &crate::config::GitSource,
+) -> anyhow::Result<Built> {
+	use anyhow::Context;
+
+	let url = &git.url;
+	if url.starts_with('-') {
+		anyhow::bail!("git URL {url} of mod {name} can't start with -");
+	}
+
+	// The repository is cloned once per mod and URL, and fetched again every time it's used.
+	let repository_directory = cache_directory.join(format!("{name}-{:08x}.git", crc32fast::hash(url.as_bytes())));
+
+	if repository_directory.exists() {
+		// Branches are updated so that a rev that names a branch gets the branch's newest commit.
+		// A failed fetch isn't fatal, so that mods that were already cloned can still be installed offline.
+		let fetched = run_git(&repository_directory, &["fetch", "--quiet", "--prune", "--force", "--tags", "origin", "+refs/heads/*:refs/heads/*"]);
+		if let Err(err) = fetched {
+			eprintln!("Could not fetch {url}, so the previously fetched commits of {name} are used: {err:#}");
+		}
+	}
+	else {
+		std::fs::create_dir_all(cache_directory).with_context(|| format!("could not create cache directory {}", cache_directory.display()))?;
+
+		let repository_directory = repository_directory.to_str().with_context(|| format!("{} is not valid UTF-8", repository_directory.display()))?;
+		run_git(cache_directory, &["clone", "--quiet", "--bare", "--", url, repository_directory])?;
+	}
+
+	let rev = git.rev.as_deref().unwrap_or("HEAD");
+	let commit =
+		run_git(&repository_directory, &["rev-parse", "--verify", "--quiet", "--end-of-options", &format!("{rev}^{{commit}}")])
+		.with_context(|| format!("{url} has no branch, tag or commit named {rev}"))?;
+	let commit = String::from_utf8(commit).context("commit hash is not valid UTF-8")?;
+	let commit = commit.trim();
+
+	let subdir = git.subdir.as_deref().unwrap_or("").trim_matches('/');
+	let (tree, info_json_path) =
+		if subdir.is_empty() { (commit.to_owned(), "info.json".to_owned()) }
+		else { (format!("{commit}:{subdir}"), format!("{subdir}/info.json")) };
+
+	let info = run_git(&repository_directory, &["show", &format!("{commit}:{info_json_path}")]).with_context(|| format!("could not read {info_json_path} of {url} at {rev}"))?;
+	let info: factorio_mods_local::ModInfo = serde_json::from_slice(&info).with_context(|| format!("could not parse {info_json_path} of {url} at {rev}"))?;
+	if info.name != *name {
+		anyhow::bail!("{info_json_path} of {url} at {rev} is for mod {}, not {name}", info.name);
+	}
+
+	let path = cache_directory.join(format!("{name}_{}-{commit}.zip", info.version));
+	if !path.exists() {
+		let path_displayable = path.display();
+
+		// The game requires the files of a zipped mod to be in a directory named after the mod's name and version.
+		let mut build_path = path.clone().into_os_string();
+		build_path.push(".new");
+		let build_path = std::path::PathBuf::from(build_path);
+		let build_path_str = build_path.to_str().with_context(|| format!("{} is not valid UTF-8", build_path.display()))?;
+		run_git(&repository_directory, &["archive", "--format=zip", &format!("--prefix={name}_{}/", info.version), "-o", build_path_str, &tree])
+			.with_context(|| format!("could not build {path_displayable}"))?;
+
+		// `git archive` of a subdirectory's tree doesn't know the commit, so it's added here.
+		if !subdir.is_empty() {
+			let result: anyhow::Result<()> = (|| {
+				let file = std::fs::OpenOptions::new().read(true).write(true).open(&build_path)?;
+				let mut zip = zip::ZipWriter::new_append(file)?;
+				zip.set_comment(commit);
+				zip.finish()?;
+				Ok(())
+			})();
+			result.with_context(|| format!("could not write commit to {}", build_path.display()))?;
+		}
+
+		std::fs::rename(&build_path, &path).with_context(|| format!("could not rename {} to {path_displayable}", build_path.display()))?;
+	}
+
+	Ok(Built { info, commit: commit.to_owned(), path })
+}
+
+/// Runs git with the given arguments in the given directory, and returns its stdout.
+fn run_git(directory: &std::path::Path, args: &[&str]) -> anyhow::Result<Vec<u8>> {
+	use anyhow::Context;
+
+	let output =
+		std::process::Command::new("git")
+		.args(args)
+		.current_dir(directory)
+		.stdin(std::process::Stdio::null())
+		.output()
+		.context("could not run git. Make sure that git is installed.")?;
+
+	if !output.status.success() {
+		anyhow::bail!("`git {}` failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+	}
+
+	Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_build() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+		let repository_directory = directory.join("repository");
+		let cache_directory = directory.join("cache");
+		std::fs::create_dir_all(repository_directory.join("src")).unwrap();
+
+		let commit = |version: &str| {
+			std::fs::write(
+				repository_directory.join("src").join("info.json"),
+				format!(r#"{{ "name": "foo", "version": "{version}", "title": "Foo", "author": "", "factorio_version": "1.1", "dependencies": ["bar >= 0.2"] }}"#),
+			).unwrap();
+			super::run_git(&repository_directory, &["add", "."]).unwrap();
+			super::run_git(&repository_directory, &["-c", "user.name=fac", "-c", "user.email=fac@localhost", "commit", "--quiet", "-m", version]).unwrap();
+		};
+
+		super::run_git(&repository_directory, &["init", "--quiet"]).unwrap();
+		commit("1.2.3");
+		super::run_git(&repository_directory, &["tag", "v1.2.3"]).unwrap();
+
+		let name = factorio_mods_common::ModName("foo".to_owned());
+		let url = format!("file://{}", repository_directory.display());
+
+		let git = crate::config::GitSource { url: url.clone(), rev: Some("v1.2.3".to_owned()), subdir: Some("src".to_owned()) };
+		let built = super::build(&cache_directory, &name, &git).unwrap();
+		assert_eq!(built.info.version.0, semver::Version::new(1, 2, 3));
+		assert_eq!(built.info.dependencies[0].name.0, "bar");
+
+		let mod_ = factorio_mods_local::InstalledMod::parse(built.path.clone()).unwrap();
+		assert_eq!(mod_.mod_type, factorio_mods_local::InstalledModType::Zipped);
+		assert_eq!(mod_.info, built.info);
+
+		// A zip built again from the same commit is installed as the same release, even if its files' times differ.
+		let installed_path = directory.join("foo_1.2.3.zip");
+		std::fs::copy(&built.path, &installed_path).unwrap();
+		std::fs::remove_dir_all(&cache_directory).unwrap();
+		let rebuilt = super::build(&cache_directory, &name, &git).unwrap();
+		assert_eq!(rebuilt.commit, built.commit);
+
+		let installed_mod = factorio_mods_local::InstalledMod::parse(installed_path).unwrap();
+		let release = super::super::source::Release {
+			name: name.clone(),
+			version: built.info.version.clone(),
+			factorio_version: built.info.factorio_version.clone(),
+			filename: "foo_1.2.3.zip".to_owned(),
+			source: super::super::GIT_SOURCE.to_owned(),
+		};
+		let source = super::GitModSource { built: [(name.clone(), rebuilt)].into_iter().collect() };
+		assert!(super::super::source::ModSource::is_installed_as(&source, &release, &installed_mod).unwrap());
+
+		// The repository is fetched again, so the default branch has the new commit.
+		commit("1.2.4");
+		let git = crate::config::GitSource { url, rev: None, subdir: Some("/src/".to_owned()) };
+		let built = super::build(&cache_directory, &name, &git).unwrap();
+		assert_eq!(built.info.version.0, semver::Version::new(1, 2, 4));
+
+		// The new commit needs to be installed.
+		let source = super::GitModSource { built: [(name.clone(), built)].into_iter().collect() };
+		assert!(!super::super::source::ModSource::is_installed_as(&source, &release, &installed_mod).unwrap());
+
+		let err = super::build(&cache_directory, &factorio_mods_common::ModName("bar".to_owned()), &git).unwrap_err();
+		assert!(err.to_string().contains("not bar"));
+
+		// Neither the URL nor the rev can be passed to git as options.
+		let git = crate::config::GitSource { url: "--upload-pack=touch pwned".to_owned(), rev: None, subdir: None };
+		super::build(&cache_directory, &name, &git).unwrap_err();
+		let git = crate::config::GitSource { url: format!("file://{}", repository_directory.display()), rev: Some("--output=pwned".to_owned()), subdir: None };
+		super::build(&cache_directory, &name, &git).unwrap_err();
+		assert!(!repository_directory.join("pwned").exists() && !cache_directory.join("pwned").exists());
+	}
+}
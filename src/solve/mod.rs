@@ -1,34 +1,106 @@
 use anyhow::Context;
 
+mod git;
+mod portal;
+mod repo;
+pub(crate) mod source;
 mod web_reader;
 mod zip;
 
+/// Options for [`compute_and_apply_diff`]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DiffOptions<'a> {
+	/// Print the changelog entries of upgraded mods along with the diff.
+	pub(crate) show_changelogs: bool,
+
+	/// Mods that the user asked to uninstall. These are removed even if they're local mods.
+	pub(crate) uninstall: &'a [factorio_mods_common::ModName],
+
+	/// Mod zip files outside the mods directory to install as local mods. They're only copied into the mods directory if the user applies the diff.
+	pub(crate) local_files: &'a [factorio_mods_local::InstalledMod],
+
+	/// Unpacked mods outside the mods directory to link into it as local mods, with the paths of the links. They're only linked if the user applies the diff.
+	pub(crate) local_links: &'a [(factorio_mods_local::InstalledMod, std::path::PathBuf)],
+
+	/// Leave installed mods alone instead of removing the ones that aren't part of the solution, and don't save the config.
+	///
+	/// This is for reqs that only describe a temporary set of mods rather than the mods the user wants installed.
+	pub(crate) keep_other_mods: bool,
+}
+
 /// Computes which old mods to uninstall and which new mods to install based on the given reqs.
 /// Asks the user for confirmation, then applies the diff.
 ///
-/// Returns true if the diff was successfully applied or empty.
+/// Returns the solution if the diff was successfully applied or empty, and `None` if the user chose not to apply it.
 pub(crate) async fn compute_and_apply_diff(
 	local_api: &factorio_mods_local::Api,
 	web_api: &factorio_mods_web::Api,
 	mut config: crate::config::Config,
 	prompt_override: Option<bool>,
-) -> anyhow::Result<()> {
-	let user_credentials = std::rc::Rc::new(crate::util::ensure_user_credentials(local_api, web_api, prompt_override).await?);
-
+	options: DiffOptions<'_>,
+) -> anyhow::Result<Option<std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>>> {
 	let game_version = local_api.game_version();
 
 	println!("Getting mod information ...");
 
-	let mods = config.mods.take().unwrap();
-	let solution_future = SolutionFuture::new(web_api, user_credentials.clone(), game_version, mods);
-	let (solution, mut reqs) = solution_future.await?;
+	let mut mods = config.mods.take().unwrap();
+
+	// The requirement for base is always the installed game version.
+	let _ = mods.remove(&factorio_mods_common::ModName("base".to_owned()));
+
+	let sources = source::Sources::new(local_api, web_api, prompt_override, &config.repositories, config.directory(), &mods).await?;
+
+	let local_mods = local_mods(local_api, &mods, options.uninstall, options.local_files, options.local_links)?;
+	let local_mod_names: std::collections::BTreeSet<_> = local_mods.iter().map(|installed_mod| installed_mod.info.name.clone()).collect();
+
+	let solution = loop {
+		let mut reqs: std::collections::BTreeMap<_, _> = mods.iter().map(|(name, entry)| (name.clone(), entry.version.clone())).collect();
+
+		// Local mods are never removed, so they're always part of the solution, unless the reqs only describe a temporary set of mods.
+		if !options.keep_other_mods {
+			for name in &local_mod_names {
+				reqs.entry(name.clone()).or_insert_with(|| factorio_mods_common::ModVersionReq(semver::VersionReq::STAR));
+			}
+		}
+
+		let optional_dependencies =
+			mods.iter()
+			.filter(|(_, entry)| entry.optional_dependencies != Default::default())
+			.map(|(name, entry)| (name.clone(), entry.optional_dependencies.clone()))
+			.collect();
+
+		let solution_future = SolutionFuture::new(&sources, game_version, reqs, optional_dependencies, &config.holds, local_mods.clone());
+		let (solution, not_found) = solution_future.await?;
+
+		let unknown_mods: Vec<_> = mods.keys().filter(|name| not_found.contains(*name)).cloned().collect();
+		if unknown_mods.is_empty() {
+			break solution;
+		}
+
+		// Only mods from the mods portal can be replaced with similarly-named ones.
+		if let Some((name, source)) =
+			unknown_mods.iter()
+			.find_map(|name| mods[name].source.as_deref().filter(|&source| source != PORTAL_SOURCE).map(|source| (name, source)))
+		{
+			anyhow::bail!(r#"mod {name} does not exist in source "{source}"."#);
+		}
+
+		fix_unknown_mods(web_api, &mut mods, unknown_mods, prompt_override).await?;
+
+		println!();
+		println!("Getting mod information ...");
+	};
 
-	let _ = reqs.remove(&factorio_mods_common::ModName("base".to_owned()));
-	config.mods = Some(reqs);
+	let solution = solution.context("no solution found.")?;
 
-	let solution =
+	let solution_versions =
+		solution.iter()
+		.filter(|installable| !matches!(installable, Installable::Base(..)))
+		.map(|installable| (package::Package::name(installable).clone(), package::Package::version(installable).clone()))
+		.collect();
+
+	let solution: std::collections::BTreeMap<_, _> =
 		solution
-		.context("no solution found.")?
 		.into_iter()
 		.filter_map(|installable|
 			if let Installable::Mod(name, release, _) = installable {
@@ -39,8 +111,8 @@ pub(crate) async fn compute_and_apply_diff(
 			})
 		.collect();
 
-	let Some((to_uninstall, to_install)) = compute_diff(solution, local_api, prompt_override)? else {
-		return Ok(());
+	let Some((to_uninstall, to_install)) = compute_diff(solution, local_api, &sources, prompt_override, options, &local_mods).await? else {
+		return Ok(None);
 	};
 
 	let mods_directory = local_api.mods_directory();
@@ -79,33 +151,218 @@ pub(crate) async fn compute_and_apply_diff(
 			installed_mod.info.name, installed_mod.info.version);
 	}
 
+	for local_file in options.local_files {
+		copy_mod_file(local_file, mods_directory)?;
+	}
+
+	for (local_link, link) in options.local_links {
+		link_mod_directory(local_link, link)?;
+	}
+
+	let sources = &sources;
 	let download_futures: futures_util::stream::FuturesUnordered<_> =
 		to_install.into_iter()
 		.map(move |(name, release)|
 			download_mod(
-				web_api,
+				sources,
 				name,
 				release,
 				mods_directory,
-				&mods_directory_canonicalized,
-				&user_credentials))
+				&mods_directory_canonicalized))
 		.collect();
 	futures_util::stream::TryStreamExt::try_for_each_concurrent(download_futures, None, futures_util::future::ok).await?;
 
-	config.save()?;
+	apply_enabled(local_api, &mods, &solution_versions)?;
+
+	if !options.keep_other_mods {
+		config.mods = Some(mods);
+		config.save()?;
+	}
+
+	Ok(Some(solution_versions))
+}
+
+/// The name of the mods portal in the `source` of config entries.
+const PORTAL_SOURCE: &str = "portal";
+
+/// The name of the source of mods whose config entries have git repositories.
+const GIT_SOURCE: &str = "git";
+
+/// The `source` of config entries for mods that are installed by the user rather than from the mods portal, like mods being developed.
+pub(crate) const LOCAL_SOURCE: &str = "local";
+
+/// Returns the installed mods that are never removed or replaced with releases from the mods portal.
+///
+/// These are unpacked mods, which are usually mods being developed and linked with `fac link`,
+/// and the installed versions that match the config entry of mods with the local source, like the exact version that `fac install` installed from a zip file.
+/// Other installed versions of these mods, like zips from the mods portal that were installed before, aren't local and are removed.
+/// Mods that the user asked to uninstall aren't local either.
+fn local_mods(
+	local_api: &factorio_mods_local::Api,
+	mods: &std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::ModEntry>,
+	uninstall: &[factorio_mods_common::ModName],
+	local_files: &[factorio_mods_local::InstalledMod],
+	local_links: &[(factorio_mods_local::InstalledMod, std::path::PathBuf)],
+) -> anyhow::Result<Vec<factorio_mods_local::InstalledMod>> {
+	let mut all_installed_mods: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+	for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+		let installed_mod = installed_mod.context("could not process an installed mod")?;
+		all_installed_mods.entry(installed_mod.info.name.clone()).or_default().push(installed_mod);
+	}
+
+	// Mod zip files that are being installed replace the installed versions of their mods.
+	for local_file in local_files {
+		all_installed_mods.insert(local_file.info.name.clone(), vec![local_file.clone()]);
+	}
+
+	// Mod directories that are being linked are unpacked mods like the ones that are already linked.
+	for (local_link, _) in local_links {
+		all_installed_mods.entry(local_link.info.name.clone()).or_default().push(local_link.clone());
+	}
+
+	for (name, entry) in mods {
+		if entry.source.as_deref() == Some(LOCAL_SOURCE) && !all_installed_mods.contains_key(name) {
+			anyhow::bail!(r#"mod {name} has source "{LOCAL_SOURCE}" but is not installed."#);
+		}
+	}
+
+	let mut result = vec![];
+
+	for (name, installed_mods) in all_installed_mods {
+		if uninstall.contains(&name) {
+			continue;
+		}
+
+		let (unpacked, zipped): (Vec<_>, Vec<_>) =
+			installed_mods.into_iter()
+			.partition(|installed_mod| installed_mod.mod_type == factorio_mods_local::InstalledModType::Unpacked);
+
+		if !unpacked.is_empty() {
+			result.extend(unpacked);
+		}
+		else if let Some(entry) = mods.get(&name).filter(|entry| entry.source.as_deref() == Some(LOCAL_SOURCE)) {
+			let matching: Vec<_> = zipped.into_iter().filter(|installed_mod| entry.version.0.matches(&installed_mod.info.version.0)).collect();
+			if matching.is_empty() {
+				anyhow::bail!(r#"mod {name} has source "{LOCAL_SOURCE}" but none of its installed versions match {}."#, entry.version);
+			}
+			result.extend(matching);
+		}
+	}
+
+	Ok(result)
+}
+
+/// Enables or disables the installed mods whose config entries say so, using the version in the solution if several are installed.
+fn apply_enabled(
+	local_api: &factorio_mods_local::Api,
+	mods: &std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::ModEntry>,
+	solution: &std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+) -> anyhow::Result<()> {
+	if mods.values().all(|entry| entry.enabled.is_none()) {
+		return Ok(());
+	}
+
+	let mods_status = local_api.mods_status().context("could not parse installed mods status")?;
+
+	// The game loads the version chosen in mod-list.json, so prefer the version in the solution, else the newest version.
+	let mut installed: std::collections::BTreeMap<_, factorio_mods_local::InstalledMod> = Default::default();
+	for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+		let installed_mod = installed_mod.context("could not process an installed mod")?;
+
+		if mods.get(&installed_mod.info.name).and_then(|entry| entry.enabled).is_none() {
+			continue;
+		}
+
+		let key = |installed_mod: &factorio_mods_local::InstalledMod| (
+			solution.get(&installed_mod.info.name) == Some(&installed_mod.info.version),
+			installed_mod.info.version.clone(),
+		);
+
+		match installed.entry(installed_mod.info.name.clone()) {
+			std::collections::btree_map::Entry::Occupied(mut entry) => if key(&installed_mod) > key(entry.get()) {
+				entry.insert(installed_mod);
+			},
+
+			std::collections::btree_map::Entry::Vacant(entry) => { entry.insert(installed_mod); },
+		}
+	}
+
+	let mut to_enable = vec![];
+	let mut to_disable = vec![];
+
+	for (name, installed_mod) in installed {
+		let enabled = mods[&name].enabled == Some(true);
+		if mods_status.get(&name).copied().unwrap_or(true) == enabled {
+			continue;
+		}
+
+		if enabled {
+			println!("    Enabling {name}");
+			to_enable.push(installed_mod);
+		}
+		else {
+			println!("    Disabling {name}");
+			to_disable.push(installed_mod);
+		}
+	}
+
+	local_api.set_enabled(&to_enable, true).context("could not enable mods")?;
+	local_api.set_enabled(&to_disable, false).context("could not disable mods")?;
 
 	Ok(())
 }
 
-fn download_mod(
+/// Offers mods with similar names or titles for each of the given requirements that don't exist on the web API,
+/// and replaces each requirement with the mod the user chooses.
+/// If the chosen mod already has a requirement of its own, that one is kept and the unknown requirement is dropped.
+///
+/// Fails if the user doesn't choose a replacement for any of them.
+async fn fix_unknown_mods<T>(
 	web_api: &factorio_mods_web::Api,
+	reqs: &mut std::collections::BTreeMap<factorio_mods_common::ModName, T>,
+	unknown_mods: Vec<factorio_mods_common::ModName>,
+	prompt_override: Option<bool>,
+) -> anyhow::Result<()> {
+	println!();
+
+	let index = crate::search_index::SearchIndex::load_or_refresh(web_api, false).await?;
+
+	for name in unknown_mods {
+		let suggestions = index.suggestions(&name);
+		if suggestions.is_empty() {
+			anyhow::bail!("mod {name} does not exist. Mod names are case-sensitive; use `fac search` to find the right name.");
+		}
+
+		println!("Mod {name} does not exist. Did you mean:");
+		for (i, suggestion) in suggestions.iter().enumerate() {
+			println!("    {}) {} ({})", i + 1, suggestion.name, suggestion.title);
+		}
+
+		let Some(choice) = crate::util::prompt_choice("Choose a mod", suggestions.len(), prompt_override)? else {
+			anyhow::bail!("mod {name} does not exist.");
+		};
+
+		let version = reqs.remove(&name).unwrap();
+		let suggestion = &suggestions[choice].name;
+		match reqs.entry(suggestion.clone()) {
+			std::collections::btree_map::Entry::Occupied(_) =>
+				// The existing entry was written by the user for the right name, so it wins over the one for the misspelled name.
+				println!("Mod {suggestion} is already in the config. Keeping its existing entry and removing {name}."),
+			std::collections::btree_map::Entry::Vacant(entry) => { entry.insert(version); },
+		}
+	}
+
+	Ok(())
+}
+
+fn download_mod<'a>(
+	sources: &'a source::Sources<'_>,
 	mod_name: factorio_mods_common::ModName,
-	release: std::rc::Rc<factorio_mods_web::ModRelease>,
+	release: std::rc::Rc<source::Release>,
 	mods_directory: &std::path::Path,
 	mods_directory_canonicalized: &std::path::Path,
-	user_credentials: &factorio_mods_common::UserCredentials,
-) -> impl std::future::Future<Output = anyhow::Result<()>> + 'static {
-	let target = mods_directory.join(&release.filename.0);
+) -> impl std::future::Future<Output = anyhow::Result<()>> + 'a {
+	let target = mods_directory.join(&release.filename);
 	let displayable_target = target.display().to_string();
 
 	let mut download_filename: std::ffi::OsString = match target.file_name() {
@@ -135,7 +392,7 @@ fn download_mod(
 
 	println!("    Installing {mod_name} {} ... downloading to {download_displayable_target} ...", release.version);
 
-	let mut chunk_stream = Box::pin(web_api.download(&release, user_credentials, None));
+	let mut chunk_stream = sources.get(&release.source).download(release.clone());
 
 	let download_file =
 		std::fs::OpenOptions::new()
@@ -146,7 +403,7 @@ fn download_mod(
 		let download_file = download_file.with_context(|| format!("could not open {download_displayable_target} for writing"))?;
 		let mut download_file = std::io::BufWriter::new(download_file);
 
-		while let Some(chunk) = futures_util::stream::TryStreamExt::try_next(&mut chunk_stream).await.context("could not download file")? {
+		while let Some(chunk) = futures_util::stream::TryStreamExt::try_next(&mut chunk_stream).await? {
 			std::io::Write::write_all(&mut download_file, &chunk)
 				.with_context(|| format!("could not write to file {download_displayable_target}"))?;
 		}
@@ -167,11 +424,74 @@ fn download_mod(
 	})
 }
 
-fn compute_diff(
-	mut solution: std::collections::BTreeMap<factorio_mods_common::ModName, std::rc::Rc<factorio_mods_web::ModRelease>>,
+/// Copies the given mod zip file into the mods directory.
+fn copy_mod_file(
+	local_file: &factorio_mods_local::InstalledMod,
+	mods_directory: &std::path::Path,
+) -> anyhow::Result<()> {
+	let name = &local_file.info.name;
+	let version = &local_file.info.version;
+
+	let path = &local_file.path;
+	let path_displayable = path.display();
+
+	let target = mods_directory.join(format!("{name}_{version}.zip"));
+	let displayable_target = target.display();
+
+	let mut download_filename = target.file_name().unwrap().to_owned();
+	download_filename.push(".new");
+	let download_target = target.with_file_name(download_filename);
+	let download_displayable_target = download_target.display();
+
+	println!("    Installing {name} {version} ... copying {path_displayable} to {download_displayable_target} ...");
+
+	std::fs::copy(path, &download_target)
+		.with_context(|| format!("could not copy {path_displayable} to {download_displayable_target}"))?;
+
+	println!("    Installing {name} {version} ... renaming {download_displayable_target} to {displayable_target}");
+
+	std::fs::rename(&download_target, &target)
+		.with_context(|| format!("could not rename {download_displayable_target} to {displayable_target}"))?;
+
+	println!("    Installing {name} {version} ... done");
+
+	Ok(())
+}
+
+/// Links the given unpacked mod into the mods directory at the given path.
+fn link_mod_directory(
+	local_link: &factorio_mods_local::InstalledMod,
+	link: &std::path::Path,
+) -> anyhow::Result<()> {
+	let name = &local_link.info.name;
+	let version = &local_link.info.version;
+
+	let target = &local_link.path;
+
+	symlink_dir(target, link).with_context(|| format!("could not link {} to {}", link.display(), target.display()))?;
+	println!("    Linked {name} {version} at {} to {}", link.display(), target.display());
+
+	Ok(())
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &std::path::Path, link: &std::path::Path) -> std::io::Result<()> {
+	std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &std::path::Path, link: &std::path::Path) -> std::io::Result<()> {
+	std::os::windows::fs::symlink_dir(target, link)
+}
+
+async fn compute_diff(
+	mut solution: std::collections::BTreeMap<factorio_mods_common::ModName, std::rc::Rc<source::Release>>,
 	local_api: &factorio_mods_local::Api,
+	sources: &source::Sources<'_>,
 	prompt_override: Option<bool>,
-) -> anyhow::Result<Option<(Vec<factorio_mods_local::InstalledMod>, Vec<(factorio_mods_common::ModName, std::rc::Rc<factorio_mods_web::ModRelease>)>)>> {
+	options: DiffOptions<'_>,
+	local_mods: &[factorio_mods_local::InstalledMod],
+) -> anyhow::Result<Option<(Vec<factorio_mods_local::InstalledMod>, Vec<(factorio_mods_common::ModName, std::rc::Rc<source::Release>)>)>> {
 	let mut all_installed_mods: std::collections::BTreeMap<_, Vec<_>> = Default::default();
 	for mod_ in local_api.installed_mods().context("could not enumerate installed mods")? {
 		let mod_ = mod_.context("could not process an installed mod")?;
@@ -182,15 +502,29 @@ fn compute_diff(
 	let mut to_install: std::collections::BTreeMap<_, _> = Default::default();
 
 	for (name, installed_mods) in all_installed_mods {
+		// Local mods are never replaced, but their other installed versions are removed.
+		if local_mods.iter().any(|local_mod| local_mod.info.name == name) {
+			if !options.keep_other_mods {
+				to_uninstall.extend(installed_mods.into_iter().filter(|installed_mod| local_mods.iter().all(|local_mod| local_mod.path != installed_mod.path)));
+			}
+			continue;
+		}
+
 		match solution.remove(&name) {
 			Some(release) => {
 				let mut already_installed = false;
 
 				for installed_mod in installed_mods {
 					if release.version == installed_mod.info.version {
-						already_installed = true;
+						// A different build of the same version is replaced.
+						if sources.get(&release.source).is_installed_as(&release, &installed_mod)? {
+							already_installed = true;
+						}
+						else {
+							to_uninstall.push(installed_mod);
+						}
 					}
-					else {
+					else if !options.keep_other_mods {
 						to_uninstall.push(installed_mod);
 					}
 				}
@@ -200,6 +534,8 @@ fn compute_diff(
 				}
 			},
 
+			None if options.keep_other_mods => (),
+
 			None =>
 				to_uninstall.extend(installed_mods),
 		}
@@ -207,6 +543,9 @@ fn compute_diff(
 
 	to_install.extend(solution);
 
+	// Saves that use the exact versions of mods that will be removed won't load with the same mods afterwards.
+	let saves_using = if to_uninstall.is_empty() { Default::default() } else { saves_using(local_api, &to_uninstall)? };
+
 	{
 		let to_upgrade: Vec<_> =
 			itertools::Itertools::sorted_by(
@@ -223,7 +562,24 @@ fn compute_diff(
 			println!();
 			println!("The following mods will be upgraded:");
 			for (installed_mod, release) in to_upgrade {
-				println!("    {} {} -> {}", installed_mod.info.name, installed_mod.info.version, release.version);
+				println!(
+					"    {} {} -> {}{}",
+					installed_mod.info.name, installed_mod.info.version, release.version,
+					used_by(&saves_using, installed_mod),
+				);
+
+				if options.show_changelogs && installed_mod.info.version < release.version {
+					match sources.get(&release.source).changelog(release.clone()).await {
+						Ok(Some(changelog)) =>
+							changelog.print(|version|
+								version.version.as_ref().is_some_and(|version|
+									*version > installed_mod.info.version.0 && *version <= release.version.0)),
+
+						Ok(None) => println!("    (no changelog)"),
+
+						Err(err) => eprintln!("    (could not get changelog: {err:#})"),
+					}
+				}
 			}
 		}
 	}
@@ -242,46 +598,181 @@ fn compute_diff(
 		println!();
 		println!("The following mods will be removed:");
 		for installed_mod in &to_uninstall {
-			println!("    {} {}", installed_mod.info.name, installed_mod.info.version);
+			println!("    {} {}{}", installed_mod.info.name, installed_mod.info.version, used_by(&saves_using, installed_mod));
 		}
 	}
 
-	if !to_install.is_empty() {
+	if !to_install.is_empty() || !options.local_files.is_empty() || !options.local_links.is_empty() {
 		println!();
 		println!("The following new mods will be installed:");
 		for (name, release) in &to_install {
 			println!("    {name} {}", release.version);
 		}
+		for local_file in options.local_files {
+			println!("    {} {} from {}", local_file.info.name, local_file.info.version, local_file.path.display());
+		}
+		for (local_link, link) in options.local_links {
+			println!("    {} {} linked at {} to {}", local_link.info.name, local_link.info.version, link.display(), local_link.path.display());
+		}
 	}
 
 	println!();
 
-	if to_uninstall.is_empty() && to_install.is_empty() {
+	let affected_saves: std::collections::BTreeSet<_> = saves_using.into_values().flatten().collect();
+
+	if to_uninstall.is_empty() && to_install.is_empty() && options.local_files.is_empty() && options.local_links.is_empty() {
 		println!("Nothing to do.");
 	}
-	else if !crate::util::prompt_continue(prompt_override)? {
-		return Ok(None);
+	else if affected_saves.is_empty() {
+		if !crate::util::prompt_continue(prompt_override)? {
+			return Ok(None);
+		}
+	}
+	else {
+		println!("The following saves use mods that will be removed, and might not load afterwards:");
+		for path in &affected_saves {
+			println!("    {}", path.display());
+		}
+		println!();
+
+		match crate::util::prompt_backup(prompt_override)? {
+			crate::util::BackupChoice::BackUpAndContinue => back_up_saves(local_api, &affected_saves)?,
+			crate::util::BackupChoice::Continue => (),
+			crate::util::BackupChoice::Abort => return Ok(None),
+		}
 	}
 
 	Ok(Some((to_uninstall, to_install)))
 }
 
+/// Returns the paths of the saves that use each of the given installed mods, for the installed mods that are used by any save.
+///
+/// Saves that can't be parsed are skipped with a warning.
+fn saves_using(
+	local_api: &factorio_mods_local::Api,
+	installed_mods: &[factorio_mods_local::InstalledMod],
+) -> anyhow::Result<std::collections::BTreeMap<(factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion), Vec<std::path::PathBuf>>> {
+	let mut result: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+
+	let installed_mods: std::collections::BTreeSet<_> =
+		installed_mods.iter()
+		.map(|installed_mod| (&installed_mod.info.name, &installed_mod.info.version))
+		.collect();
+
+	for save in local_api.saves().context("could not enumerate saves")? {
+		let save = match save {
+			Ok(save) => save,
+			Err(err) => {
+				eprintln!("Could not read the mods of a save: {:#}", anyhow::Error::new(err));
+				continue;
+			},
+		};
+
+		for save_mod in save.mods {
+			if installed_mods.contains(&(&save_mod.name, &save_mod.version)) {
+				result.entry((save_mod.name, save_mod.version)).or_default().push(save.path.clone());
+			}
+		}
+	}
+
+	Ok(result)
+}
+
+/// Formats the names of the saves that use the given installed mod, for appending to the line that lists the mod.
+fn used_by(
+	saves_using: &std::collections::BTreeMap<(factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion), Vec<std::path::PathBuf>>,
+	installed_mod: &factorio_mods_local::InstalledMod,
+) -> String {
+	let Some(paths) = saves_using.get(&(installed_mod.info.name.clone(), installed_mod.info.version.clone())) else {
+		return String::new();
+	};
+
+	let names = paths.iter().map(|path| path.file_stem().unwrap_or(path.as_os_str()).to_string_lossy());
+	format!(" (used by saves: {})", itertools::join(names, ", "))
+}
+
+/// Copies the given saves to the `fac-backups` directory inside the saves directory.
+///
+/// Each copy's name has the current time appended, so that earlier backups of the same save are not overwritten.
+fn back_up_saves(local_api: &factorio_mods_local::Api, paths: &std::collections::BTreeSet<std::path::PathBuf>) -> anyhow::Result<()> {
+	let backups_directory = local_api.saves_directory().join("fac-backups");
+	std::fs::create_dir_all(&backups_directory)
+		.with_context(|| format!("could not create backups directory {}", backups_directory.display()))?;
+
+	let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+
+	for path in paths {
+		let mut file_name = path.file_stem().unwrap_or(path.as_os_str()).to_owned();
+		file_name.push(format!("-{now}.zip"));
+		let backup_path = backups_directory.join(file_name);
+
+		println!("Backing up {} to {} ...", path.display(), backup_path.display());
+		std::fs::copy(path, &backup_path)
+			.with_context(|| format!("could not copy {} to {}", path.display(), backup_path.display()))?;
+		println!("Backing up {} to {} ... done", path.display(), backup_path.display());
+	}
+
+	println!();
+
+	Ok(())
+}
+
+/// Gets the `info.json` of the given mod release without downloading all of it.
+pub(crate) async fn get_info_json(
+	web_api: &factorio_mods_web::Api,
+	release: std::rc::Rc<factorio_mods_web::ModRelease>,
+	user_credentials: std::rc::Rc<factorio_mods_common::UserCredentials>,
+) -> anyhow::Result<factorio_mods_local::ModInfo> {
+	let mut web_reader =
+		web_reader::WebReader::new(web_api, release, user_credentials).await
+		.context("could not create web reader")?;
+	let mod_info =
+		zip::find_info_json(&mut web_reader).await
+		.context("could not get info.json")?;
+	Ok(mod_info)
+}
+
+/// Gets the changelog of the given mod release, if it has one.
+pub(crate) async fn get_changelog(
+	web_api: &factorio_mods_web::Api,
+	release: std::rc::Rc<factorio_mods_web::ModRelease>,
+	user_credentials: std::rc::Rc<factorio_mods_common::UserCredentials>,
+) -> anyhow::Result<Option<crate::changelog::Changelog>> {
+	let mut web_reader =
+		web_reader::WebReader::new(web_api, release, user_credentials).await
+		.context("could not create web reader")?;
+
+	let changelog = match zip::find_file(&mut web_reader, b"changelog.txt").await {
+		Ok(changelog) => changelog,
+		Err(zip::Error::FileNotFound) => return Ok(None),
+		Err(err) => return Err(anyhow::Error::new(err).context("could not get changelog.txt")),
+	};
+
+	let changelog = String::from_utf8_lossy(&changelog);
+	Ok(Some(crate::changelog::Changelog::parse(&changelog)))
+}
+
 struct SolutionFuture<'a> {
 	packages: Vec<Installable>,
 	already_fetching: std::collections::BTreeSet<std::rc::Rc<factorio_mods_common::ModName>>,
 	pending: Vec<CacheFuture<'a>>,
-	web_api: &'a factorio_mods_web::Api,
-	user_credentials: std::rc::Rc<factorio_mods_common::UserCredentials>,
+	sources: &'a source::Sources<'a>,
 	game_version: &'a factorio_mods_common::ReleaseVersion,
 	reqs: std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ModVersionReq>,
+	optional_dependencies: std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::OptionalDependencies>,
+	wanted_optional_dependencies: std::collections::BTreeSet<factorio_mods_common::ModName>,
+	holds: &'a std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+	not_found: std::collections::BTreeSet<factorio_mods_common::ModName>,
 }
 
 impl<'a> SolutionFuture<'a> {
 	fn new(
-		web_api: &'a factorio_mods_web::Api,
-		user_credentials: std::rc::Rc<factorio_mods_common::UserCredentials>,
+		sources: &'a source::Sources<'a>,
 		game_version: &'a factorio_mods_common::ReleaseVersion,
 		mut reqs: std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ModVersionReq>,
+		optional_dependencies: std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::OptionalDependencies>,
+		holds: &'a std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+		local_mods: Vec<factorio_mods_local::InstalledMod>,
 	) -> Self {
 		let packages = vec![Installable::Base(factorio_mods_common::ModName("base".to_owned()), game_version.clone())];
 
@@ -289,25 +780,39 @@ impl<'a> SolutionFuture<'a> {
 			packages,
 			already_fetching: Default::default(),
 			pending: Default::default(),
-			web_api,
-			user_credentials,
+			sources,
 			game_version,
 			reqs: Default::default(),
+			optional_dependencies,
+			wanted_optional_dependencies: Default::default(),
+			holds,
+			not_found: Default::default(),
 		};
 
+		// Local mods are pinned to their installed versions, so the mods portal isn't asked about them. Their dependencies still are.
+		for installed_mod in &local_mods {
+			result.already_fetching.insert(installed_mod.info.name.clone().into());
+		}
+
+		for installed_mod in local_mods {
+			get_dependencies(
+				&installed_mod.info.name,
+				&installed_mod.info.dependencies,
+				&result.optional_dependencies,
+				&mut result.wanted_optional_dependencies,
+				&mut result.already_fetching,
+				&mut result.pending,
+				sources,
+			);
+
+			result.packages.push(Installable::Local(installed_mod.info.name, installed_mod.info.version, installed_mod.info.dependencies));
+		}
+
 		for mod_name in reqs.keys() {
-			get(mod_name.clone().into(), &mut result.already_fetching, &mut result.pending, web_api);
+			get(mod_name.clone().into(), &mut result.already_fetching, &mut result.pending, sources);
 		}
 
-		reqs.insert(factorio_mods_common::ModName("base".to_owned()), factorio_mods_common::ModVersionReq(semver::VersionReq {
-			comparators: vec![semver::Comparator {
-				op: semver::Op::Exact,
-				major: game_version.0.major,
-				minor: Some(game_version.0.minor),
-				patch: Some(game_version.0.patch),
-				pre: game_version.0.pre.clone(),
-			}],
-		}));
+		reqs.insert(factorio_mods_common::ModName("base".to_owned()), exact_req(game_version));
 
 		result.reqs = reqs;
 
@@ -315,10 +820,10 @@ impl<'a> SolutionFuture<'a> {
 	}
 }
 
-impl<'a> std::future::Future for SolutionFuture<'a> {
+impl std::future::Future for SolutionFuture<'_> {
 	type Output = anyhow::Result<(
 		Option<Vec<Installable>>,
-		std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ModVersionReq>,
+		std::collections::BTreeSet<factorio_mods_common::ModName>,
 	)>;
 
 	fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
@@ -331,17 +836,17 @@ impl<'a> std::future::Future for SolutionFuture<'a> {
 
 			match &mut this.pending[i] {
 				CacheFuture::GetMod(get_mod) => match get_mod {
-					Some((mod_name, f)) => match f.as_mut().poll(cx) {
+					Some((_, f)) => match f.as_mut().poll(cx) {
 						std::task::Poll::Pending => (),
 
-						std::task::Poll::Ready(Ok(mod_)) => {
+						std::task::Poll::Ready(Ok(Some(releases))) => {
 							let (mod_name, _) = get_mod.take().unwrap();
 
 							println!("    Getting {mod_name} ... done");
 
-							for release in mod_.releases {
+							for release in releases {
 								let game_version_req = factorio_mods_common::VersionReqMatcher {
-									version_req: &release.info_json.factorio_version.0,
+									version_req: &release.factorio_version.0,
 									is_base: true,
 								};
 								if !package::VersionReq::matches(&game_version_req, this.game_version) {
@@ -352,30 +857,24 @@ impl<'a> std::future::Future for SolutionFuture<'a> {
 
 								println!("        Getting {mod_name} {} info.json ...", release.version);
 
-								let web_api = this.web_api;
-								let user_credentials = this.user_credentials.clone();
 								new.push(CacheFuture::GetInfoJson(Some((
 									mod_name.clone(),
 									release.clone(),
-									Box::pin(async move {
-										let mut web_reader =
-											web_reader::WebReader::new(web_api, release, user_credentials).await
-											.context("could not create web reader")?;
-										let mod_info =
-											zip::find_info_json(&mut web_reader).await
-											.context("could not get info.json")?;
-										Ok(mod_info)
-									}),
+									this.sources.get(&release.source).mod_info(release),
 								))));
 							}
 						},
 
 						// Don't fail the whole process due to non-existent deps. Releases with unmet deps will be handled when computing the solution.
-						std::task::Poll::Ready(Err(factorio_mods_web::Error::StatusCode(_, http::StatusCode::NOT_FOUND))) =>
-							drop(get_mod.take()),
+						// Non-existent mods that were directly required are reported by the caller.
+						std::task::Poll::Ready(Ok(None)) => {
+							let (mod_name, _) = get_mod.take().unwrap();
+							println!("    Getting {mod_name} ... not found");
+							this.not_found.insert((*mod_name).clone());
+						},
 
 						std::task::Poll::Ready(Err(err)) =>
-							return std::task::Poll::Ready(Err(anyhow::Error::new(err).context(format!("could not get mod info for {mod_name}")))),
+							return std::task::Poll::Ready(Err(err)),
 					},
 
 					None => unreachable!(),
@@ -386,9 +885,15 @@ impl<'a> std::future::Future for SolutionFuture<'a> {
 						std::task::Poll::Ready(Ok(mod_info)) => {
 							let (mod_name, release, _) = get_info_json.take().unwrap();
 
-							for dep in mod_info.dependencies.iter().filter(|dep| dep.kind == package::DependencyKind::Required && dep.name.0 != "base") {
-								get(dep.name.clone().into(), &mut this.already_fetching, &mut new, this.web_api);
-							}
+							get_dependencies(
+								&mod_info.name,
+								&mod_info.dependencies,
+								&this.optional_dependencies,
+								&mut this.wanted_optional_dependencies,
+								&mut this.already_fetching,
+								&mut new,
+								this.sources,
+							);
 
 							this.packages.push(Installable::Mod(mod_info.name, release.clone(), mod_info.dependencies));
 
@@ -421,7 +926,20 @@ impl<'a> std::future::Future for SolutionFuture<'a> {
 		println!("Getting mod information ... done");
 
 		let packages = std::mem::take(&mut this.packages);
-		let reqs = std::mem::take(&mut this.reqs);
+		let mut reqs = std::mem::take(&mut this.reqs);
+		let not_found = std::mem::take(&mut this.not_found);
+
+		if reqs.keys().any(|name| not_found.contains(name)) {
+			return std::task::Poll::Ready(Ok((None, not_found)));
+		}
+
+		// Optional dependencies that were asked for are required like the config's own mods,
+		// except for those that don't exist or have no releases for this game version.
+		for name in std::mem::take(&mut this.wanted_optional_dependencies) {
+			if !reqs.contains_key(&name) && packages.iter().any(|package| package::Package::name(package) == &name) {
+				reqs.insert(name, factorio_mods_common::ModVersionReq(semver::VersionReq::STAR));
+			}
+		}
 
 		println!();
 		println!("Computing solution...");
@@ -438,43 +956,151 @@ impl<'a> std::future::Future for SolutionFuture<'a> {
 			.collect();
 
 		let solution =
-			package::compute_solution(packages, &solver_reqs)
+			package::compute_solution(held_packages(&packages, this.holds, None), &solver_reqs)
 			.context("could not compute solution.")?;
 
-		std::task::Poll::Ready(Ok((solution, reqs)))
+		if solution.is_none() {
+			if let Some(err) = holds_preventing_solution(&packages, this.holds, &solver_reqs) {
+				return std::task::Poll::Ready(Err(err));
+			}
+		}
+
+		std::task::Poll::Ready(Ok((solution, not_found)))
 	}
 }
 
-fn get(
+/// Returns the packages without the releases of held mods other than the held versions.
+///
+/// The hold on `ignored_hold` is left out, to find out whether it is the one that prevents a solution.
+fn held_packages(
+	packages: &[Installable],
+	holds: &std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+	ignored_hold: Option<&factorio_mods_common::ModName>,
+) -> Vec<Installable> {
+	packages.iter()
+	.filter(|package| {
+		let name = package::Package::name(*package);
+		Some(name) == ignored_hold || holds.get(name).is_none_or(|version| version == package::Package::version(*package))
+	})
+	.cloned()
+	.collect()
+}
+
+/// If there is no solution, explains which holds prevent one, if any.
+fn holds_preventing_solution(
+	packages: &[Installable],
+	holds: &std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+	solver_reqs: &std::collections::BTreeMap<&factorio_mods_common::ModName, factorio_mods_common::VersionReqMatcher<'_>>,
+) -> Option<anyhow::Error> {
+	let relevant_holds: std::collections::BTreeMap<_, _> =
+		holds.iter()
+		.filter(|(name, _)| packages.iter().any(|package| package::Package::name(package) == *name))
+		.collect();
+	if relevant_holds.is_empty() {
+		return None;
+	}
+
+	let has_solution = |packages| matches!(package::compute_solution(packages, solver_reqs), Ok(Some(_)));
+
+	let blocking: Vec<_> =
+		relevant_holds.iter()
+		.filter(|(name, _)| has_solution(held_packages(packages, holds, Some(name))))
+		.collect();
+
+	let (blocking, together) =
+		if !blocking.is_empty() {
+			(blocking, false)
+		}
+		else if has_solution(packages.to_owned()) {
+			(relevant_holds.iter().collect(), true)
+		}
+		else {
+			return None;
+		};
+
+	let held = itertools::join(blocking.iter().map(|(name, version)| format!("{name} {version}")), ", ");
+	let names = itertools::join(blocking.iter().map(|(name, _)| name), " ");
+
+	Some(anyhow::anyhow!(
+		"no solution found, because of the {}hold{} on {held}. Run `fac unhold {names}` to allow {} to be updated.",
+		if together { "combined " } else { "" },
+		if blocking.len() == 1 { "" } else { "s" },
+		if blocking.len() == 1 { "it" } else { "them" },
+	))
+}
+
+/// Returns a requirement that only matches the given version.
+pub(crate) fn exact_req(version: &factorio_mods_common::ReleaseVersion) -> factorio_mods_common::ModVersionReq {
+	factorio_mods_common::ModVersionReq(semver::VersionReq {
+		comparators: vec![semver::Comparator {
+			op: semver::Op::Exact,
+			major: version.0.major,
+			minor: Some(version.0.minor),
+			patch: Some(version.0.patch),
+			pre: version.0.pre.clone(),
+		}],
+	})
+}
+
+fn get<'a>(
 	mod_name: std::rc::Rc<factorio_mods_common::ModName>,
 	already_fetching: &mut std::collections::BTreeSet<std::rc::Rc<factorio_mods_common::ModName>>,
-	new: &mut Vec<CacheFuture<'_>>,
-	web_api: &factorio_mods_web::Api,
+	new: &mut Vec<CacheFuture<'a>>,
+	sources: &'a source::Sources<'_>,
 ) {
 	if already_fetching.insert(mod_name.clone()) {
 		println!("    Getting {mod_name} ...");
 
-		let f = Box::pin(web_api.get(&mod_name));
+		let f = sources.releases(&mod_name);
 		new.push(CacheFuture::GetMod(Some((mod_name, f))));
 	}
 }
 
+/// Starts fetching the required dependencies of the given mod, and the optional dependencies that the config asks for.
+fn get_dependencies<'a>(
+	mod_name: &factorio_mods_common::ModName,
+	dependencies: &[factorio_mods_common::Dependency],
+	optional_dependencies: &std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::OptionalDependencies>,
+	wanted_optional_dependencies: &mut std::collections::BTreeSet<factorio_mods_common::ModName>,
+	already_fetching: &mut std::collections::BTreeSet<std::rc::Rc<factorio_mods_common::ModName>>,
+	new: &mut Vec<CacheFuture<'a>>,
+	sources: &'a source::Sources<'_>,
+) {
+	let optional_dependencies = optional_dependencies.get(mod_name);
+
+	for dep in dependencies.iter().filter(|dep| dep.name.0 != "base") {
+		match dep.kind {
+			package::DependencyKind::Required => (),
+
+			package::DependencyKind::Optional if optional_dependencies.is_some_and(|optional_dependencies| optional_dependencies.includes(&dep.name)) => {
+				wanted_optional_dependencies.insert(dep.name.clone());
+			},
+
+			package::DependencyKind::Optional |
+			package::DependencyKind::Conflicts => continue,
+		}
+
+		get(dep.name.clone().into(), already_fetching, new, sources);
+	}
+}
+
 enum CacheFuture<'a> {
 	GetMod(Option<(
 		std::rc::Rc<factorio_mods_common::ModName>,
-		std::pin::Pin<Box<dyn std::future::Future<Output = Result<factorio_mods_web::Mod, factorio_mods_web::Error>>>>,
+		futures_util::future::LocalBoxFuture<'a, anyhow::Result<Option<Vec<source::Release>>>>,
 	)>),
 	GetInfoJson(Option<(
 		std::rc::Rc<factorio_mods_common::ModName>,
-		std::rc::Rc<factorio_mods_web::ModRelease>,
-		std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<factorio_mods_local::ModInfo>> + 'a>>,
+		std::rc::Rc<source::Release>,
+		futures_util::future::LocalBoxFuture<'a, anyhow::Result<factorio_mods_local::ModInfo>>,
 	)>),
 }
 
 #[derive(Clone, Debug)]
 enum Installable {
 	Base(factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion),
-	Mod(factorio_mods_common::ModName, std::rc::Rc<factorio_mods_web::ModRelease>, Vec<factorio_mods_common::Dependency>),
+	Mod(factorio_mods_common::ModName, std::rc::Rc<source::Release>, Vec<factorio_mods_common::Dependency>),
+	Local(factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion, Vec<factorio_mods_common::Dependency>),
 }
 
 impl package::Package for Installable {
@@ -485,13 +1111,15 @@ impl package::Package for Installable {
 	fn name(&self) -> &Self::Name {
 		match self {
 			Installable::Base(name, _) |
-			Installable::Mod(name, _, _) => name,
+			Installable::Mod(name, _, _) |
+			Installable::Local(name, _, _) => name,
 		}
 	}
 
 	fn version(&self) -> &Self::Version {
 		match self {
-			Installable::Base(_, version) => version,
+			Installable::Base(_, version) |
+			Installable::Local(_, version, _) => version,
 			Installable::Mod(_, release, _) => &release.version,
 		}
 	}
@@ -499,7 +1127,148 @@ impl package::Package for Installable {
 	fn dependencies(&self) -> &[Self::Dependency] {
 		match self {
 			Installable::Base(_, _) => &[],
-			Installable::Mod(_, _, dependencies) => dependencies,
+			Installable::Mod(_, _, dependencies) |
+			Installable::Local(_, _, dependencies) => dependencies,
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	fn name(name: &str) -> factorio_mods_common::ModName {
+		factorio_mods_common::ModName(name.to_owned())
+	}
+
+	fn version(version: &str) -> factorio_mods_common::ReleaseVersion {
+		factorio_mods_common::ReleaseVersion(version.parse().unwrap())
+	}
+
+	fn dependency(dependency: &str) -> factorio_mods_common::Dependency {
+		serde_json::from_value(serde_json::Value::String(dependency.to_owned())).unwrap()
+	}
+
+	fn portal_release(mod_name: &str, mod_version: &str, dependencies: &[&str]) -> super::Installable {
+		super::Installable::Mod(
+			name(mod_name),
+			std::rc::Rc::new(super::source::Release {
+				name: name(mod_name),
+				version: version(mod_version),
+				factorio_version: factorio_mods_common::ModVersionReq("1.1".parse().unwrap()),
+				filename: format!("{mod_name}_{mod_version}.zip"),
+				source: super::PORTAL_SOURCE.to_owned(),
+			}),
+			dependencies.iter().copied().map(dependency).collect(),
+		)
+	}
+
+	/// Returns the sorted names and versions of the solution's packages, and whether each one is local, or `None` if there is no solution.
+	fn solve(packages: Vec<super::Installable>, reqs: &[&str]) -> Option<Vec<(String, String, bool)>> {
+		let version_reqs: Vec<_> = reqs.iter().map(|&req| (name(req), factorio_mods_common::ModVersionReq(semver::VersionReq::STAR))).collect();
+		let solver_reqs =
+			version_reqs.iter()
+			.map(|(name, version_req)| (name, factorio_mods_common::VersionReqMatcher { version_req: &version_req.0, is_base: false }))
+			.collect();
+
+		let solution = package::compute_solution(packages, &solver_reqs).ok()??;
+		let mut solution: Vec<_> =
+			solution.iter()
+			.map(|installable| (
+				package::Package::name(installable).0.clone(),
+				package::Package::version(installable).to_string(),
+				matches!(installable, super::Installable::Local(..)),
+			))
+			.collect();
+		solution.sort();
+		Some(solution)
+	}
+
+	#[test]
+	fn test_solution_with_local_mod() {
+		// foo is local, and depends on bar from the mods portal. The portal's release of foo isn't in the packages because local mods are pinned.
+		let packages = vec![
+			super::Installable::Base(name("base"), version("1.1.100")),
+			super::Installable::Local(name("foo"), version("1.0.0"), vec![dependency("base >= 1.1"), dependency("bar >= 2.0")]),
+			portal_release("bar", "1.0.0", &[]),
+			portal_release("bar", "2.0.0", &["baz"]),
+			portal_release("bar", "2.1.0", &["base >= 2.0"]),
+			portal_release("baz", "0.1.0", &[]),
+		];
+
+		assert_eq!(solve(packages.clone(), &["base", "foo"]), Some(vec![
+			("bar".to_owned(), "2.0.0".to_owned(), false),
+			("base".to_owned(), "1.1.100".to_owned(), false),
+			("baz".to_owned(), "0.1.0".to_owned(), false),
+			("foo".to_owned(), "1.0.0".to_owned(), true),
+		]));
+
+		// The local mod's dependencies must be satisfiable.
+		let packages: Vec<_> = packages.into_iter().filter(|package| package::Package::name(package).0 != "baz").collect();
+		assert_eq!(solve(packages, &["base", "foo"]), None);
+	}
+
+	#[test]
+	fn test_local_mods() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let directory = temp_dir.path();
+		let install_directory = directory.join("install");
+		let user_directory = directory.join("user");
+		let mods_directory = user_directory.join("mods");
+		std::fs::create_dir_all(install_directory.join("data").join("base")).unwrap();
+		std::fs::write(install_directory.join("data").join("base").join("info.json"), r#"{ "name": "base", "version": "1.1.100" }"#).unwrap();
+		std::fs::create_dir_all(&mods_directory).unwrap();
+		std::fs::write(user_directory.join("player-data.json"), "{}").unwrap();
+		std::fs::write(mods_directory.join("mod-list.json"), r#"{ "mods": [] }"#).unwrap();
+
+		let write_unpacked = |mod_name: &str, mod_version: &str| {
+			let mod_directory = mods_directory.join(mod_name);
+			std::fs::create_dir_all(&mod_directory).unwrap();
+			std::fs::write(
+				mod_directory.join("info.json"),
+				format!(r#"{{ "name": "{mod_name}", "version": "{mod_version}", "title": "", "author": "", "factorio_version": "1.1" }}"#),
+			).unwrap();
+		};
+
+		// foo is unpacked, and a stale zip from the mods portal is still installed.
+		write_unpacked("foo", "1.0.0");
+		crate::test_util::write_mod_zip(&mods_directory.join("foo_0.9.0.zip"), "foo", "0.9.0", &[]);
+
+		// bar was installed from a zip file, after an older version was installed from the mods portal.
+		crate::test_util::write_mod_zip(&mods_directory.join("bar_1.0.0.zip"), "bar", "1.0.0", &[]);
+		crate::test_util::write_mod_zip(&mods_directory.join("bar_2.0.0-pre.zip"), "bar", "2.0.0-pre", &[]);
+
+		// baz is unpacked, but the user asked to uninstall it.
+		write_unpacked("baz", "1.0.0");
+
+		// qux is an ordinary mod from the mods portal.
+		crate::test_util::write_mod_zip(&mods_directory.join("qux_1.0.0.zip"), "qux", "1.0.0", &[]);
+
+		let local_api = factorio_mods_local::Api::new(&install_directory, &user_directory, None).unwrap();
+
+		let mut mods: std::collections::BTreeMap<_, crate::config::ModEntry> = Default::default();
+		mods.insert(name("foo"), factorio_mods_common::ModVersionReq(semver::VersionReq::STAR).into());
+		let mut bar: crate::config::ModEntry = super::exact_req(&version("2.0.0-pre")).into();
+		bar.source = Some(super::LOCAL_SOURCE.to_owned());
+		mods.insert(name("bar"), bar);
+		mods.insert(name("qux"), factorio_mods_common::ModVersionReq(semver::VersionReq::STAR).into());
+
+		let local_mods = super::local_mods(&local_api, &mods, &[name("baz")], &[], &[]).unwrap();
+		let mut local_mods: Vec<_> =
+			local_mods.iter()
+			.map(|installed_mod| (installed_mod.info.name.0.clone(), installed_mod.info.version.to_string(), installed_mod.path.file_name().unwrap().to_str().unwrap().to_owned()))
+			.collect();
+		local_mods.sort();
+		assert_eq!(local_mods, [
+			("bar".to_owned(), "2.0.0-pre".to_owned(), "bar_2.0.0-pre.zip".to_owned()),
+			("foo".to_owned(), "1.0.0".to_owned(), "foo".to_owned()),
+		]);
+
+		// Without the uninstall, baz is local too.
+		let local_mods = super::local_mods(&local_api, &mods, &[], &[], &[]).unwrap();
+		assert!(local_mods.iter().any(|installed_mod| installed_mod.info.name == name("baz")));
+
+		// A local mod must have an installed version that matches its entry.
+		mods.get_mut(&name("bar")).unwrap().version = super::exact_req(&version("3.0.0"));
+		let err = super::local_mods(&local_api, &mods, &[], &[], &[]).unwrap_err();
+		assert!(err.to_string().contains("none of its installed versions match"));
+	}
+}
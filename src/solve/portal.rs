@@ -0,0 +1,104 @@
+/// The mods portal at <https://mods.factorio.com/>, as a [`super::source::ModSource`]
+pub(super) struct PortalSource<'a> {
+	web_api: &'a factorio_mods_web::Api,
+
+	/// The user's credentials, which are only needed to download release files, so the user is only asked to log in when that happens.
+	/// All requests that need them wait for the same login.
+	user_credentials: futures_util::future::Shared<futures_util::future::LocalBoxFuture<'a, Result<std::rc::Rc<factorio_mods_common::UserCredentials>, std::rc::Rc<anyhow::Error>>>>,
+
+	/// The releases that [`super::source::ModSource::releases`] returned, which the other methods need to find the release files.
+	releases: std::cell::RefCell<std::collections::BTreeMap<
+		(factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion),
+		std::rc::Rc<factorio_mods_web::ModRelease>,
+	>>,
+}
+
+impl<'a> PortalSource<'a> {
+	pub(super) fn new(
+		local_api: &'a factorio_mods_local::Api,
+		web_api: &'a factorio_mods_web::Api,
+		prompt_override: Option<bool>,
+	) -> Self {
+		let user_credentials: futures_util::future::LocalBoxFuture<'a, _> = Box::pin(async move {
+			crate::util::ensure_user_credentials(local_api, web_api, prompt_override).await
+			.map(std::rc::Rc::new)
+			.map_err(std::rc::Rc::new)
+		});
+
+		PortalSource {
+			web_api,
+			user_credentials: futures_util::FutureExt::shared(user_credentials),
+			releases: Default::default(),
+		}
+	}
+
+	async fn user_credentials(&self) -> anyhow::Result<std::rc::Rc<factorio_mods_common::UserCredentials>> {
+		self.user_credentials.clone().await.map_err(|err| anyhow::anyhow!("{err:#}"))
+	}
+
+	fn portal_release(&self, release: &super::source::Release) -> anyhow::Result<std::rc::Rc<factorio_mods_web::ModRelease>> {
+		self.releases.borrow().get(&(release.name.clone(), release.version.clone())).cloned()
+		.ok_or_else(|| anyhow::anyhow!("{} {} is not a release on the mods portal", release.name, release.version))
+	}
+}
+
+impl super::source::ModSource for PortalSource<'_> {
+	fn releases(&self, name: &factorio_mods_common::ModName) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<Vec<super::source::Release>>>> {
+		let name = name.clone();
+		let f = self.web_api.get(&name);
+
+		Box::pin(async move {
+			let mod_ = match f.await {
+				Ok(mod_) => mod_,
+				Err(factorio_mods_web::Error::StatusCode(_, http::StatusCode::NOT_FOUND)) => return Ok(None),
+				Err(err) => return Err(anyhow::Error::new(err).context(format!("could not get mod info for {name}"))),
+			};
+
+			let mut releases = self.releases.borrow_mut();
+
+			let result =
+				mod_.releases.into_iter()
+				.map(|release| {
+					let result = super::source::Release {
+						name: name.clone(),
+						version: release.version.clone(),
+						factorio_version: release.info_json.factorio_version.clone(),
+						filename: release.filename.0.clone(),
+						source: super::PORTAL_SOURCE.to_owned(),
+					};
+					releases.insert((name.clone(), release.version.clone()), std::rc::Rc::new(release));
+					result
+				})
+				.collect();
+
+			Ok(Some(result))
+		})
+	}
+
+	fn mod_info(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<factorio_mods_local::ModInfo>> {
+		Box::pin(async move {
+			let release = self.portal_release(&release)?;
+			super::get_info_json(self.web_api, release, self.user_credentials().await?).await
+		})
+	}
+
+	fn download(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::stream::LocalBoxStream<'_, anyhow::Result<bytes::Bytes>> {
+		let release = match self.portal_release(&release) {
+			Ok(release) => release,
+			Err(err) => return Box::pin(futures_util::stream::once(futures_util::future::err(err))),
+		};
+
+		let user_credentials = futures_util::stream::once(self.user_credentials());
+		Box::pin(futures_util::stream::TryStreamExt::try_flatten(futures_util::stream::TryStreamExt::map_ok(user_credentials, move |user_credentials|
+			futures_util::stream::TryStreamExt::map_err(
+				self.web_api.download(&release, &user_credentials, None),
+				|err| anyhow::Error::new(err).context("could not download file")))))
+	}
+
+	fn changelog(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<crate::changelog::Changelog>>> {
+		Box::pin(async move {
+			let release = self.portal_release(&release)?;
+			super::get_changelog(self.web_api, release, self.user_credentials().await?).await
+		})
+	}
+}
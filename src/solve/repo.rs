@@ -0,0 +1,183 @@
+/// A mod repository configured in the config file, as a [`super::source::ModSource`]
+pub(super) struct RepoSource<'a> {
+	name: String,
+	location: Location,
+	web_api: &'a factorio_mods_web::Api,
+	index: crate::repo::Index,
+}
+
+/// Where a mod repository is.
+enum Location {
+	Directory(std::path::PathBuf),
+
+	/// The URL of the root of the repository, without a trailing `/`
+	Http(String),
+}
+
+impl<'a> RepoSource<'a> {
+	/// Loads the index of the given repository.
+	///
+	/// Relative paths are resolved against `config_directory`.
+	pub(super) async fn load(
+		name: String,
+		repository: &crate::config::Repository,
+		config_directory: &std::path::Path,
+		web_api: &'a factorio_mods_web::Api,
+	) -> anyhow::Result<Self> {
+		use anyhow::Context;
+
+		let url = &repository.url;
+
+		let location =
+			if url.starts_with("http://") || url.starts_with("https://") {
+				Location::Http(url.trim_end_matches('/').to_owned())
+			}
+			else if let Some(path) = url.strip_prefix("file://") {
+				Location::Directory(path.into())
+			}
+			else {
+				Location::Directory(config_directory.join(url))
+			};
+
+		let index = match &location {
+			Location::Directory(directory) => {
+				let index_file_path = directory.join(crate::repo::INDEX_FILE_NAME);
+				std::fs::read(&index_file_path)
+				.with_context(|| format!("could not read index file {}", index_file_path.display()))?
+			},
+
+			Location::Http(base) => {
+				let index_url = format!("{base}/{}", crate::repo::INDEX_FILE_NAME);
+				futures_util::TryStreamExt::try_fold(web_api.get_file(&index_url), vec![], |mut index, chunk| {
+					index.extend_from_slice(&chunk);
+					futures_util::future::ok(index)
+				}).await
+				.with_context(|| format!("could not download index file {index_url}"))?
+			},
+		};
+		let index = serde_json::from_slice(&index).with_context(|| format!(r#"could not parse index file of repository "{name}""#))?;
+
+		Ok(RepoSource {
+			name,
+			location,
+			web_api,
+			index,
+		})
+	}
+
+	fn index_release(&self, release: &super::source::Release) -> anyhow::Result<&crate::repo::IndexRelease> {
+		self.index.mods.get(&release.name).into_iter().flatten().find(|index_release| index_release.version == release.version)
+		.ok_or_else(|| anyhow::anyhow!(r#"{} {} is not a release in repository "{}""#, release.name, release.version, self.name))
+	}
+}
+
+impl super::source::ModSource for RepoSource<'_> {
+	fn releases(&self, name: &factorio_mods_common::ModName) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<Vec<super::source::Release>>>> {
+		let result = self.index.mods.get(name).map(|releases|
+			releases.iter()
+			.map(|release| super::source::Release {
+				name: name.clone(),
+				version: release.version.clone(),
+				factorio_version: release.factorio_version.clone(),
+				filename: format!("{name}_{}.zip", release.version),
+				source: self.name.clone(),
+			})
+			.collect());
+		Box::pin(futures_util::future::ok(result))
+	}
+
+	fn mod_info(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<factorio_mods_local::ModInfo>> {
+		// The index has everything from the info.json that the solver needs, so the zip file doesn't need to be read.
+		let result = self.index_release(&release).map(|index_release| factorio_mods_local::ModInfo {
+			name: release.name.clone(),
+			author: vec![],
+			title: factorio_mods_common::ModTitle(release.name.0.clone()),
+			description: None,
+			version: index_release.version.clone(),
+			factorio_version: index_release.factorio_version.clone(),
+			homepage: None,
+			dependencies: index_release.dependencies.clone(),
+		});
+		Box::pin(futures_util::future::ready(result))
+	}
+
+	fn download(&self, release: std::rc::Rc<super::source::Release>) -> futures_util::stream::LocalBoxStream<'_, anyhow::Result<bytes::Bytes>> {
+		use anyhow::Context;
+
+		let file = match self.index_release(&release) {
+			Ok(index_release) => &index_release.file,
+			Err(err) => return Box::pin(futures_util::stream::once(futures_util::future::err(err))),
+		};
+
+		match &self.location {
+			Location::Directory(directory) => {
+				let path = directory.join(file);
+				let result =
+					std::fs::read(&path)
+					.map(bytes::Bytes::from)
+					.with_context(|| format!("could not read file {}", path.display()));
+				Box::pin(futures_util::stream::once(futures_util::future::ready(result)))
+			},
+
+			Location::Http(base) => {
+				let url = format!("{base}/{file}");
+				Box::pin(futures_util::stream::TryStreamExt::map_err(
+					self.web_api.get_file(&url),
+					move |err| anyhow::Error::new(err).context(format!("could not download file {url}"))))
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[tokio::test]
+	async fn test_load() {
+		use super::super::source::ModSource;
+
+		let temp_dir = tempfile::tempdir().unwrap();
+		let config_directory = temp_dir.path();
+		let directory = config_directory.join("repository");
+		crate::test_util::write_repository(&directory);
+
+		let web_api = factorio_mods_web::Api::new().unwrap();
+
+		let foo = factorio_mods_common::ModName("foo".to_owned());
+		let bar = factorio_mods_common::ModName("bar".to_owned());
+
+		// Relative paths are relative to the config file's directory.
+		for url in ["repository".to_owned(), format!("file://{}", directory.display())] {
+			let repository = crate::config::Repository { url, priority: 1 };
+			let source = super::RepoSource::load("lan".to_owned(), &repository, config_directory, &web_api).await.unwrap();
+
+			let releases = source.releases(&foo).await.unwrap().unwrap();
+			assert_eq!(releases.iter().map(|release| release.version.to_string()).collect::<Vec<_>>(), ["1.0.0", "1.1.0"]);
+			assert!(releases.iter().all(|release| release.source == "lan"));
+			assert_eq!(releases[1].filename, "foo_1.1.0.zip");
+
+			assert!(source.releases(&factorio_mods_common::ModName("baz".to_owned())).await.unwrap().is_none());
+
+			let bar_release = std::rc::Rc::new(source.releases(&bar).await.unwrap().unwrap().remove(0));
+			let info = source.mod_info(bar_release.clone()).await.unwrap();
+			assert_eq!(info.name, bar);
+			assert_eq!(info.dependencies[0].name, foo);
+
+			let downloaded: Vec<u8> = futures_util::TryStreamExt::try_concat(
+				futures_util::TryStreamExt::map_ok(source.download(bar_release), |chunk| chunk.to_vec())).await.unwrap();
+			assert_eq!(downloaded, std::fs::read(directory.join("b").join("c").join("bar_2.0.0.zip")).unwrap());
+
+			let missing_release = std::rc::Rc::new(super::super::source::Release {
+				name: foo.clone(),
+				version: factorio_mods_common::ReleaseVersion(semver::Version::new(3, 0, 0)),
+				factorio_version: releases[0].factorio_version.clone(),
+				filename: "foo_3.0.0.zip".to_owned(),
+				source: "lan".to_owned(),
+			});
+			assert!(source.mod_info(missing_release.clone()).await.is_err());
+			assert!(futures_util::TryStreamExt::try_next(&mut source.download(missing_release)).await.is_err());
+		}
+
+		let repository = crate::config::Repository { url: "missing".to_owned(), priority: 1 };
+		assert!(super::RepoSource::load("lan".to_owned(), &repository, config_directory, &web_api).await.is_err());
+	}
+}
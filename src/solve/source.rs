@@ -0,0 +1,166 @@
+/// A place that mods can be installed from, like the mods portal.
+pub(crate) trait ModSource {
+	/// Lists the releases of the given mod. Returns `None` if the source doesn't have the mod.
+	fn releases(&self, name: &factorio_mods_common::ModName) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<Vec<Release>>>>;
+
+	/// Gets the `info.json` of the given release, which has the release's dependencies.
+	fn mod_info(&self, release: std::rc::Rc<Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<factorio_mods_local::ModInfo>>;
+
+	/// Fetches the contents of the given release's zip file.
+	fn download(&self, release: std::rc::Rc<Release>) -> futures_util::stream::LocalBoxStream<'_, anyhow::Result<bytes::Bytes>>;
+
+	/// Returns whether the given installed mod, which has the same name and version as the given release, is that release.
+	///
+	/// Sources whose releases can change without a new version, like git repositories, check the installed file.
+	fn is_installed_as(&self, release: &Release, installed_mod: &factorio_mods_local::InstalledMod) -> anyhow::Result<bool> {
+		let _ = (release, installed_mod);
+		Ok(true)
+	}
+
+	/// Gets the changelog of the given release, if it has one.
+	fn changelog(&self, release: std::rc::Rc<Release>) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<crate::changelog::Changelog>>> {
+		let _ = release;
+		Box::pin(futures_util::future::ok(None))
+	}
+}
+
+/// A release of a mod in a [`ModSource`]
+#[derive(Debug)]
+pub(crate) struct Release {
+	/// The name of the mod.
+	pub(crate) name: factorio_mods_common::ModName,
+
+	/// The version of the release.
+	pub(crate) version: factorio_mods_common::ReleaseVersion,
+
+	/// The versions of the game that the release supports.
+	pub(crate) factorio_version: factorio_mods_common::ModVersionReq,
+
+	/// The filename to install the release as.
+	pub(crate) filename: String,
+
+	/// The name of the source that the release is from, which the source sets.
+	pub(crate) source: String,
+}
+
+/// The sources that mods can be installed from, by name.
+pub(crate) struct Sources<'a> {
+	by_name: std::collections::BTreeMap<String, Box<dyn ModSource + 'a>>,
+
+	/// The names of the sources in descending order of priority. Mods without a chosen source are installed from the first source that has them.
+	by_priority: Vec<String>,
+
+	/// The sources that the config entries chose.
+	chosen: std::collections::BTreeMap<factorio_mods_common::ModName, String>,
+}
+
+impl<'a> Sources<'a> {
+	/// Creates the mods portal source, loads the indexes of the given repositories, and builds the mods that have git repositories.
+	pub(crate) async fn new(
+		local_api: &'a factorio_mods_local::Api,
+		web_api: &'a factorio_mods_web::Api,
+		prompt_override: Option<bool>,
+		repositories: &std::collections::BTreeMap<String, crate::config::Repository>,
+		config_directory: &std::path::Path,
+		mods: &std::collections::BTreeMap<factorio_mods_common::ModName, crate::config::ModEntry>,
+	) -> anyhow::Result<Self> {
+		let mut sources: std::collections::BTreeMap<_, Box<dyn ModSource + 'a>> = Default::default();
+		sources.insert(super::PORTAL_SOURCE.to_owned(), Box::new(super::portal::PortalSource::new(local_api, web_api, prompt_override)));
+
+		let mut priorities = vec![(super::PORTAL_SOURCE.to_owned(), 0)];
+
+		// Repositories that couldn't be loaded are skipped, so that an unreachable repository only breaks the mods that need it.
+		let mut unavailable: std::collections::BTreeMap<_, anyhow::Error> = Default::default();
+
+		for (name, repository) in repositories {
+			if name == super::PORTAL_SOURCE || name == super::LOCAL_SOURCE || name == super::GIT_SOURCE {
+				anyhow::bail!(r#"repository "{name}" has the name of a builtin source. Rename it in the config file."#);
+			}
+
+			match super::repo::RepoSource::load(name.clone(), repository, config_directory, web_api).await {
+				Ok(source) => {
+					sources.insert(name.clone(), Box::new(source));
+					priorities.push((name.clone(), repository.priority));
+				},
+
+				Err(err) => {
+					eprintln!(r#"Skipping repository "{name}": {err:#}"#);
+					unavailable.insert(name.clone(), err);
+				},
+			}
+		}
+
+		// Sources with the same priority are asked in order of name, so the order doesn't change between runs.
+		priorities.sort_by(|(name1, priority1), (name2, priority2)| priority2.cmp(priority1).then_with(|| name1.cmp(name2)));
+		let by_priority = priorities.into_iter().map(|(name, _)| name).collect();
+
+		let mut mod_sources = std::collections::BTreeMap::new();
+		let mut git_mods = std::collections::BTreeMap::new();
+
+		for (name, entry) in mods {
+			if let Some(git) = &entry.git {
+				mod_sources.insert(name.clone(), super::GIT_SOURCE.to_owned());
+				git_mods.insert(name.clone(), git.clone());
+				continue;
+			}
+
+			let Some(source) = &entry.source else {
+				continue;
+			};
+
+			// Local mods are installed by the user, so they aren't looked up in any source.
+			if source == super::LOCAL_SOURCE {
+				continue;
+			}
+
+			if let Some(err) = unavailable.get(source) {
+				anyhow::bail!(r#"mod {name} has source "{source}", but the repository could not be loaded: {err:#}"#);
+			}
+
+			if !sources.contains_key(source) {
+				anyhow::bail!(
+					r#"mod {name} has unknown source "{source}". The supported sources are {}."#,
+					itertools::join(sources.keys().map(String::as_str).chain(std::iter::once(super::LOCAL_SOURCE)).map(|source| format!(r#""{source}""#)), ", "),
+				);
+			}
+
+			mod_sources.insert(name.clone(), source.clone());
+		}
+
+		// Mods from git repositories are only ever installed from their own repositories, so this source isn't in `by_priority`.
+		if !git_mods.is_empty() {
+			sources.insert(super::GIT_SOURCE.to_owned(), Box::new(super::git::GitModSource::new(&git_mods)?));
+		}
+
+		Ok(Sources {
+			by_name: sources,
+			by_priority,
+			chosen: mod_sources,
+		})
+	}
+
+	/// Lists the releases of the given mod from the source that its config entry chose,
+	/// else from the source with the highest priority that has the mod.
+	pub(crate) fn releases(&self, name: &factorio_mods_common::ModName) -> futures_util::future::LocalBoxFuture<'_, anyhow::Result<Option<Vec<Release>>>> {
+		if let Some(source_name) = self.chosen.get(name) {
+			return self.get(source_name).releases(name);
+		}
+
+		let name = name.clone();
+
+		Box::pin(async move {
+			for source_name in &self.by_priority {
+				if let Some(releases) = self.get(source_name).releases(&name).await? {
+					return Ok(Some(releases));
+				}
+			}
+
+			Ok(None)
+		})
+	}
+
+	/// Returns the source with the given name.
+	pub(crate) fn get(&self, source_name: &str) -> &(dyn ModSource + 'a) {
+		&*self.by_name[source_name]
+	}
+}
@@ -64,7 +64,7 @@ impl futures_util::io::AsyncRead for WebReader<'_> {
 					DataRegion::Download(download) => match download.as_mut().poll(cx) {
 						std::task::Poll::Ready(download) => {
 							let (reader, content) =
-								download.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+								download.map_err(std::io::Error::other)?;
 
 							*region = DataRegion::Downloaded(content);
 
@@ -88,7 +88,7 @@ impl futures_util::io::AsyncRead for WebReader<'_> {
 						Box::pin(
 							futures_util::stream::TryStreamExt::map_err(
 								response,
-								|err| std::io::Error::new(std::io::ErrorKind::Other, err))) as _);
+								std::io::Error::other)) as _);
 				let download = download_region(reader, key, this.len);
 				let _ = this.content_cache.insert((key, DataRegion::Download(Box::pin(download))));
 			}
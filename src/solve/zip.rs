@@ -13,6 +13,7 @@ pub(super) enum Error {
 	FileLocalHeaderCorrupt(FileMetaCorruptReason),
 	FileMetadataCorrupt,
 	FileNotFound,
+	FileTooLarge,
 	Io(std::io::Error),
 	UnsupportedCompressionMethod(u16),
 }
@@ -23,14 +24,15 @@ impl std::fmt::Display for Error {
 			Error::CentralDirectoryEntryCorrupt(record_number, reason) => write!(f, "central-directory record #{record_number} is corrupt: {reason}"),
 			Error::EndOfCentralDirectorRecordCorrupt => f.write_str("end-of-central-directory record is corrupt"),
 			Error::EndOfCentralDirectorRecordNotFound => f.write_str("could not find end-of-central-directory record"),
-			Error::FileCorrupt => f.write_str("info.json is corrupt"),
+			Error::FileCorrupt => f.write_str("file is corrupt"),
 			Error::FileInvalidJson(_) => f.write_str("info.json could not be parsed"),
-			Error::FileLocalHeaderCorrupt(reason) => write!(f, "info.json local-header record is corrupt: {reason}"),
-			Error::FileMetadataCorrupt => f.write_str("info.json file-local-header record has different metadata than its central-directory-entry record"),
-			Error::FileNotFound => f.write_str("info.json not found"),
+			Error::FileLocalHeaderCorrupt(reason) => write!(f, "file local-header record is corrupt: {reason}"),
+			Error::FileMetadataCorrupt => f.write_str("file-local-header record has different metadata than its central-directory-entry record"),
+			Error::FileNotFound => f.write_str("file not found"),
+			Error::FileTooLarge => write!(f, "file is larger than the maximum of {MAX_FILE_LEN} bytes"),
 			Error::Io(_) => f.write_str("I/O error"),
 			Error::UnsupportedCompressionMethod(compression_method) =>
-				write!(f, "info.json is compressed with method {compression_method} but only Deflated and Stored are supported"),
+				write!(f, "file is compressed with method {compression_method} but only Deflated and Stored are supported"),
 		}
 	}
 }
@@ -47,6 +49,7 @@ impl std::error::Error for Error {
 			Error::FileLocalHeaderCorrupt(_) => None,
 			Error::FileMetadataCorrupt => None,
 			Error::FileNotFound => None,
+			Error::FileTooLarge => None,
 			Error::Io(err) => Some(err),
 			Error::UnsupportedCompressionMethod(_) => None,
 		}
@@ -69,6 +72,20 @@ impl std::fmt::Display for FileMetaCorruptReason {
 pub(super) async fn find_info_json(
 	reader: &mut (impl futures_util::io::AsyncRead + futures_util::io::AsyncSeek + Unpin),
 ) -> Result<factorio_mods_local::ModInfo, Error> {
+	let info_json = find_file(reader, b"info.json").await?;
+	let info_json = serde_json::from_slice(&info_json).map_err(Error::FileInvalidJson)?;
+	Ok(info_json)
+}
+
+/// The largest file that [`find_file`] reads. It's only used for small files like `info.json` and `changelog.txt`,
+/// so larger files are treated as an error instead of being read into memory.
+const MAX_FILE_LEN: u64 = 16 * 1024 * 1024;
+
+/// Finds the file with the given name in the top-level directory of the mod zip, and returns its decompressed contents.
+pub(super) async fn find_file(
+	reader: &mut (impl futures_util::io::AsyncRead + futures_util::io::AsyncSeek + Unpin),
+	filename: &[u8],
+) -> Result<Vec<u8>, Error> {
 	// PKZIP spec: https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
 
 	// Find the end-of-central-directory record
@@ -122,34 +139,50 @@ pub(super) async fn find_info_json(
 		return Err(Error::FileNotFound);
 	}
 
-	let mut info_json_entry = None;
+	let mut file_entry = None;
 	for i in 0..num_central_directory_entries {
 		let entry = CentralDirectoryEntry::parse(reader, i).await?;
-		if entry.file_meta.filename.ends_with(b"/info.json") && entry.file_meta.filename.iter().filter(|&&b| b == b'/').count() == 1 {
-			info_json_entry = Some(entry);
+		let is_match =
+			entry.file_meta.filename.iter().filter(|&&b| b == b'/').count() == 1 &&
+			entry.file_meta.filename.split(|&b| b == b'/').nth(1) == Some(filename);
+		if is_match {
+			file_entry = Some(entry);
 			break;
 		}
 	}
-	let info_json_entry = info_json_entry.ok_or(Error::FileNotFound)?;
+	let file_entry = file_entry.ok_or(Error::FileNotFound)?;
 
-	let _ = futures_util::io::AsyncSeekExt::seek(reader, std::io::SeekFrom::Start(info_json_entry.local_header_pos)).await.map_err(Error::Io)?;
+	let _ = futures_util::io::AsyncSeekExt::seek(reader, std::io::SeekFrom::Start(file_entry.local_header_pos)).await.map_err(Error::Io)?;
 
-	let info_json_file_local_header = FileLocalHeader::parse(reader).await?;
+	let file_local_header = FileLocalHeader::parse(reader).await?;
 
-	// Ideally info_json_file_local_header.0 would be == to info_json_entry.file_meta, but some zips have malformed info_json_file_local_header.0
-	// where crc32, compressed_size and uncompressed_size are all set to 0. Known cases are miniloader 1.11.2 and miniloader 1.11.3
+	// Ideally file_local_header.0 would be == to file_entry.file_meta, but some zips have malformed file_local_header.0
+	// where crc32, compressed_size and uncompressed_size are all set to 0. Known cases are the info.json of miniloader 1.11.2 and miniloader 1.11.3
 	//
-	// So just check the filename matches and nothing else. And make sure to use compressed_size and crc32 from info_json_entry.file_meta
-	if info_json_file_local_header.0.filename != info_json_entry.file_meta.filename {
+	// So just check the filename matches and nothing else. And make sure to use compressed_size and crc32 from file_entry.file_meta
+	if file_local_header.0.filename != file_entry.file_meta.filename {
 		return Err(Error::FileMetadataCorrupt);
 	}
 
-	let mut buf = vec![0_u8; info_json_entry.file_meta.compressed_size as usize];
+	// The sizes come from the zip, so they're checked before anything is allocated for them.
+	if file_entry.file_meta.compressed_size > MAX_FILE_LEN {
+		return Err(Error::FileTooLarge);
+	}
+
+	let mut buf = vec![0_u8; file_entry.file_meta.compressed_size as usize];
 	futures_util::io::AsyncReadExt::read_exact(reader, &mut buf).await.map_err(Error::Io)?;
 
-	let reader = Reader::new(info_json_entry.file_meta.compression_method, &buf, info_json_entry.file_meta.crc32)?;
-	let info_json = serde_json::from_reader(reader).map_err(Error::FileInvalidJson)?;
-	Ok(info_json)
+	// The uncompressed size in the header can't be trusted either, so the decompressed contents are limited by reading at most one byte more than the maximum.
+	let reader = Reader::new(file_entry.file_meta.compression_method, &buf, file_entry.file_meta.crc32)?;
+	let mut contents = Vec::with_capacity(std::cmp::min(file_entry.file_meta.uncompressed_size, MAX_FILE_LEN) as usize);
+	match std::io::Read::read_to_end(&mut std::io::Read::take(reader, MAX_FILE_LEN + 1), &mut contents) {
+		Ok(len) if len as u64 > MAX_FILE_LEN => Err(Error::FileTooLarge),
+		Ok(_) => Ok(contents),
+		Err(err) => match err.downcast::<Error>() {
+			Ok(err) => Err(err),
+			Err(err) => Err(Error::Io(err)),
+		},
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -301,10 +334,42 @@ impl std::io::Read for Reader<'_> {
 		if result == 0 {
 			let crc32 = self.hasher.clone().finalize();
 			if crc32 != self.expected_crc32 {
-				return Err(std::io::Error::new(std::io::ErrorKind::Other, Error::FileCorrupt));
+				return Err(std::io::Error::other(Error::FileCorrupt));
 			}
 		}
 
 		Ok(result)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	fn mod_zip(contents: &[u8]) -> Vec<u8> {
+		let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+		zip.start_file("foo_1.0.0/changelog.txt", zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored)).unwrap();
+		std::io::Write::write_all(&mut zip, contents).unwrap();
+		zip.finish().unwrap().into_inner()
+	}
+
+	#[tokio::test]
+	async fn test_find_file() {
+		let zip = mod_zip(b"Version: 1.0.0");
+		let contents = super::find_file(&mut futures_util::io::Cursor::new(&zip), b"changelog.txt").await.unwrap();
+		assert_eq!(contents, b"Version: 1.0.0");
+
+		let err = super::find_file(&mut futures_util::io::Cursor::new(&zip), b"info.json").await.unwrap_err();
+		assert!(matches!(err, super::Error::FileNotFound));
+	}
+
+	#[tokio::test]
+	async fn test_find_file_too_large() {
+		let mut zip = mod_zip(b"Version: 1.0.0");
+
+		// Claim that the file is almost 4 GiB in the central directory.
+		let central_directory_entry_pos = zip.windows(4).position(|window| window == [0x50, 0x4b, 0x01, 0x02]).unwrap();
+		zip[(central_directory_entry_pos + 20)..(central_directory_entry_pos + 28)].fill(0xff);
+
+		let err = super::find_file(&mut futures_util::io::Cursor::new(&zip), b"changelog.txt").await.unwrap_err();
+		assert!(matches!(err, super::Error::FileTooLarge));
+	}
+}
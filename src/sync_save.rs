@@ -0,0 +1,203 @@
+#[derive(clap::Args)]
+pub(crate) struct SubCommand {
+	#[arg(help = "path of the save file, or the name of a save in the game's saves directory", value_parser)]
+	save: std::path::PathBuf,
+}
+
+impl SubCommand {
+	pub(crate) async fn run(
+		self,
+		local_api: &factorio_mods_local::Api,
+		web_api: &factorio_mods_web::Api,
+		mut config: crate::config::Config,
+		prompt_override: Option<bool>,
+	) -> anyhow::Result<()> {
+		use anyhow::Context;
+
+		let path = resolve_save_path(local_api, self.save);
+		let save = factorio_mods_local::Save::parse(path).context("could not parse save")?;
+
+		println!("Save {} was made with game version {}.", save.path.display(), save.game_version);
+		if save.game_version > *local_api.game_version() {
+			println!("The installed game version {} is older, so the game might not be able to load this save.", local_api.game_version());
+		}
+
+		let builtin_mods: Vec<_> = save.mods.iter().filter(|mod_| mod_.name.0 != "base" && crate::util::BUILTIN_MODS.contains(&&*mod_.name.0)).collect();
+		if !builtin_mods.is_empty() {
+			println!(
+				"The save also uses {}, which {} part of the game and must be enabled in the game.",
+				itertools::join(builtin_mods.iter().map(|mod_| &mod_.name), ", "),
+				if builtin_mods.len() == 1 { "is" } else { "are" },
+			);
+		}
+
+		println!();
+
+		let mut installed_versions: std::collections::BTreeMap<_, std::collections::BTreeSet<_>> = Default::default();
+		for mod_ in local_api.installed_mods().context("could not enumerate installed mods")? {
+			let mod_ = mod_.context("could not process an installed mod")?;
+			installed_versions.entry(mod_.info.name).or_default().insert(mod_.info.version);
+		}
+
+		let save_mods: Vec<_> = save.mods.into_iter().filter(|mod_| !crate::util::BUILTIN_MODS.contains(&&*mod_.name.0)).collect();
+
+		println!("Checking mods portal ...");
+
+		let mut portal_mods: futures_util::stream::FuturesOrdered<_> =
+			save_mods.iter().map(|save_mod| async move {
+				match web_api.get(&save_mod.name).await {
+					Ok(mod_) => Ok(Some(mod_)),
+					Err(factorio_mods_web::Error::StatusCode(_, http::StatusCode::NOT_FOUND)) => Ok(None),
+					Err(err) => Err(anyhow::Error::new(err).context(format!("could not retrieve mod {}", save_mod.name))),
+				}
+			}).collect();
+
+		// Mods whose exact version is already installed are used as they are if the portal doesn't have that version any more.
+		let mut reqs = std::collections::BTreeMap::new();
+		let mut installed_only = std::collections::BTreeMap::new();
+
+		for save_mod in &save_mods {
+			let portal_mod = futures_util::TryStreamExt::try_next(&mut portal_mods).await?.unwrap();
+
+			let is_installed = installed_versions.get(&save_mod.name).is_some_and(|versions| versions.contains(&save_mod.version));
+
+			let is_on_portal = portal_mod.as_ref().is_some_and(|portal_mod|
+				portal_mod.releases.iter().any(|release| release.version == save_mod.version));
+
+			if is_on_portal {
+				reqs.insert(save_mod.name.clone(), crate::solve::exact_req(&save_mod.version));
+			}
+			else if is_installed {
+				println!("    {} {} is not on the mods portal, but is already installed.", save_mod.name, save_mod.version);
+				installed_only.insert(save_mod.name.clone(), save_mod.version.clone());
+			}
+			else if portal_mod.is_some() {
+				println!(
+					"    {} {} no longer exists on the mods portal. The newest compatible release will be used instead.",
+					save_mod.name, save_mod.version,
+				);
+				reqs.insert(save_mod.name.clone(), factorio_mods_common::ModVersionReq(semver::VersionReq::STAR));
+			}
+			else {
+				anyhow::bail!("mod {} does not exist on the mods portal and is not installed.", save_mod.name);
+			}
+		}
+
+		println!("Checking mods portal ... done");
+		println!();
+
+		let mut wanted = installed_only;
+
+		if !reqs.is_empty() {
+			config.mods = Some(reqs.into_iter().map(|(name, req)| (name, req.into())).collect());
+
+			let options = crate::solve::DiffOptions { keep_other_mods: true, ..Default::default() };
+			let Some(solution) = crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override, options).await? else {
+				return Ok(());
+			};
+
+			wanted.extend(solution);
+		}
+
+		enable_exactly(local_api, &wanted, prompt_override)?;
+
+		Ok(())
+	}
+}
+
+/// Resolves the given save to a path.
+///
+/// Saves that don't exist at the given path are looked up in the game's saves directory, with or without the `.zip` extension.
+fn resolve_save_path(local_api: &factorio_mods_local::Api, save: std::path::PathBuf) -> std::path::PathBuf {
+	if save.exists() || save.iter().count() != 1 {
+		return save;
+	}
+
+	let mut path = local_api.saves_directory().join(&save);
+	if !path.exists() && path.extension() != Some("zip".as_ref()) {
+		path.as_mut_os_string().push(".zip");
+	}
+	path
+}
+
+/// Enables exactly the given versions of the given mods, and disables all other installed mods.
+fn enable_exactly(
+	local_api: &factorio_mods_local::Api,
+	wanted: &std::collections::BTreeMap<factorio_mods_common::ModName, factorio_mods_common::ReleaseVersion>,
+	prompt_override: Option<bool>,
+) -> anyhow::Result<()> {
+	use anyhow::Context;
+
+	let mods_status = local_api.mods_status().context("could not parse installed mods status")?;
+	let active_versions = local_api.active_versions().context("could not parse installed mods status")?;
+
+	let mut all_installed_mods: std::collections::BTreeMap<_, Vec<_>> = Default::default();
+	for installed_mod in local_api.installed_mods().context("could not enumerate installed mods")? {
+		let installed_mod = installed_mod.context("could not process an installed mod")?;
+		all_installed_mods.entry(installed_mod.info.name.clone()).or_default().push(installed_mod);
+	}
+
+	let mut to_enable = vec![];
+	let mut to_disable = vec![];
+
+	// Only mods whose status actually changes are listed.
+	let mut newly_enabled = vec![];
+	let mut newly_disabled = vec![];
+
+	for (name, mut installed_mods) in all_installed_mods {
+		let is_enabled = mods_status.get(&name).copied().unwrap_or(true);
+
+		if let Some(version) = wanted.get(&name) {
+			let Some(index) = installed_mods.iter().position(|installed_mod| installed_mod.info.version == *version) else {
+				continue;
+			};
+
+			// The game loads the version chosen in mod-list.json, else the newest version.
+			let active_version =
+				active_versions.get(&name)
+				.or_else(|| installed_mods.iter().map(|installed_mod| &installed_mod.info.version).max());
+			if !is_enabled || active_version != Some(version) {
+				newly_enabled.push(format!("{name} {version}"));
+			}
+
+			to_enable.push(installed_mods.swap_remove(index));
+		}
+		else {
+			if is_enabled {
+				newly_disabled.push(name);
+			}
+
+			to_disable.push(installed_mods.swap_remove(0));
+		}
+	}
+
+	if newly_enabled.is_empty() && newly_disabled.is_empty() {
+		println!("All mods of the save are enabled and no other mods are enabled.");
+		return Ok(());
+	}
+
+	if !newly_enabled.is_empty() {
+		println!("The following mods will be enabled:");
+		for name in &newly_enabled {
+			println!("    {name}");
+		}
+		println!();
+	}
+
+	if !newly_disabled.is_empty() {
+		println!("The following mods will be disabled:");
+		for name in &newly_disabled {
+			println!("    {name}");
+		}
+		println!();
+	}
+
+	if !crate::util::prompt_continue(prompt_override)? {
+		return Ok(());
+	}
+
+	local_api.set_enabled(&to_enable, true).context("could not enable mods")?;
+	local_api.set_enabled(&to_disable, false).context("could not disable mods")?;
+
+	Ok(())
+}
@@ -0,0 +1,31 @@
+/// Writes a repository of mod zip files to the given directory and indexes it.
+///
+/// The repository has foo 1.0.0 and 1.1.0 in `a/`, and bar 2.0.0 in `b/c/`. It also has a zip file that isn't a mod, and a duplicate of foo 1.0.0.
+pub(crate) fn write_repository(directory: &std::path::Path) {
+	write_mod_zip(&directory.join("a").join("foo_1.1.0.zip"), "foo", "1.1.0", &[]);
+	write_mod_zip(&directory.join("a").join("foo_1.0.0.zip"), "foo", "1.0.0", &[]);
+	write_mod_zip(&directory.join("b").join("c").join("bar_2.0.0.zip"), "bar", "2.0.0", &["foo >= 1.1"]);
+	write_mod_zip(&directory.join("z").join("foo_1.0.0.zip"), "foo", "1.0.0", &[]);
+	std::fs::write(directory.join("a").join("not-a-mod.zip"), b"not a zip file").unwrap();
+	std::fs::write(directory.join("a").join("readme.txt"), b"").unwrap();
+
+	crate::repo::write_index(directory).unwrap();
+}
+
+/// Writes a mod zip file with an `info.json` with the given name, version and dependencies, creating its parent directories.
+pub(crate) fn write_mod_zip(path: &std::path::Path, name: &str, version: &str, dependencies: &[&str]) {
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+	let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+	let options = zip::write::FileOptions::default();
+	zip.start_file(format!("{name}_{version}/info.json"), options).unwrap();
+	serde_json::to_writer(&mut zip, &serde_json::json!({
+		"name": name,
+		"version": version,
+		"title": name,
+		"author": "",
+		"factorio_version": "1.1",
+		"dependencies": dependencies,
+	})).unwrap();
+	zip.finish().unwrap();
+}
@@ -14,11 +14,12 @@ impl SubCommand {
 	) -> anyhow::Result<()> {
 		let mods = config.mods.as_mut().unwrap();
 
-		for mod_ in self.names {
-			mods.remove(&mod_);
+		for mod_ in &self.names {
+			mods.remove(mod_);
 		}
 
-		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override).await?;
+		let options = crate::solve::DiffOptions { uninstall: &self.names, ..Default::default() };
+		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override, options).await?;
 
 		Ok(())
 	}
@@ -1,5 +1,7 @@
 #[derive(clap::Args)]
 pub(crate) struct SubCommand {
+	#[arg(help = "Show the changelogs of mods that will be upgraded", long = "changelog")]
+	changelog: bool,
 }
 
 impl SubCommand {
@@ -10,7 +12,7 @@ impl SubCommand {
 		config: crate::config::Config,
 		prompt_override: Option<bool>,
 	) -> anyhow::Result<()> {
-		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override).await?;
+		crate::solve::compute_and_apply_diff(local_api, web_api, config, prompt_override, crate::solve::DiffOptions { show_changelogs: self.changelog, ..Default::default() }).await?;
 
 		Ok(())
 	}
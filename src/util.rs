@@ -1,10 +1,18 @@
 use anyhow::Context;
 
+/// Mods that are part of the game rather than the mods portal, so they are neither downloaded nor managed.
+pub(crate) const BUILTIN_MODS: [&str; 4] = ["base", "elevated-rails", "quality", "space-age"];
+
 pub(crate) async fn ensure_user_credentials(
 	local_api: &factorio_mods_local::Api,
 	web_api: &factorio_mods_web::Api,
 	prompt_override: Option<bool>,
 ) -> anyhow::Result<factorio_mods_common::UserCredentials> {
+	// Credentials are never sent to servers other than the official mods portal, so there's no need to log in for them.
+	if !web_api.is_official() {
+		return Ok(factorio_mods_common::UserCredentials { username: Default::default(), token: Default::default() });
+	}
+
 	let mut existing_username = match local_api.user_credentials() {
 		Ok(user_credentials) =>
 			return Ok(user_credentials),
@@ -77,9 +85,70 @@ pub(crate) fn prompt_continue(prompt_override: Option<bool>) -> anyhow::Result<b
 			match &*choice {
 				"y" | "Y" => return Ok(true),
 				"n" | "N" => return Ok(false),
-				_ => continue,
+				_ => (),
 			}
 		},
 	}
+}
 
+/// The user's answer to [`prompt_backup`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum BackupChoice {
+	BackUpAndContinue,
+	Continue,
+	Abort,
+}
+
+/// Asks the user whether to back up something before continuing.
+///
+/// `-y` backs up and continues, and `-n` aborts.
+pub(crate) fn prompt_backup(prompt_override: Option<bool>) -> anyhow::Result<BackupChoice> {
+	const PROMPT: &str = "Back up and continue, continue without backing up, or abort? [b/c/a]: ";
+
+	match prompt_override {
+		Some(true) => {
+			println!("{PROMPT}b");
+			Ok(BackupChoice::BackUpAndContinue)
+		},
+
+		Some(false) => {
+			println!("{PROMPT}a");
+			Ok(BackupChoice::Abort)
+		},
+
+		None => loop {
+			let choice = rprompt::prompt_reply_stdout(PROMPT).context("could not read backup response")?;
+			match &*choice {
+				"b" | "B" => return Ok(BackupChoice::BackUpAndContinue),
+				"c" | "C" => return Ok(BackupChoice::Continue),
+				"a" | "A" => return Ok(BackupChoice::Abort),
+				_ => (),
+			}
+		},
+	}
+}
+
+/// Asks the user to choose one of `num_choices` numbered choices.
+///
+/// Returns the zero-based index of the choice, or `None` if the user chose none of them.
+/// Since there is no sensible default choice, `-y` and `-n` both choose none of them.
+pub(crate) fn prompt_choice(prompt: &str, num_choices: usize, prompt_override: Option<bool>) -> anyhow::Result<Option<usize>> {
+	let prompt = format!("{prompt} [1-{num_choices}], or leave empty for none: ");
+
+	if prompt_override.is_some() {
+		println!("{prompt}");
+		return Ok(None);
+	}
+
+	loop {
+		let choice = rprompt::prompt_reply_stdout(&prompt).context("could not read choice")?;
+		if choice.is_empty() {
+			return Ok(None);
+		}
+
+		match choice.parse::<usize>() {
+			Ok(choice) if (1..=num_choices).contains(&choice) => return Ok(Some(choice - 1)),
+			_ => (),
+		}
+	}
 }